@@ -1,7 +1,6 @@
-use futures::StreamExt as _;
 use rand::SeedableRng as _;
 use rand_chacha::ChaCha8Rng;
-use three_by_three::{ReprCube, Tables, get_incremental_solutions_stream};
+use three_by_three::{ReprCube, SolveOptions, Tables, solve};
 
 pub fn main() {
     let tables = Box::leak(Box::new(Tables::new("tables").unwrap()));
@@ -13,13 +12,18 @@ pub fn main() {
 
         // cube.pretty_print();
 
-        let mut stream = get_incremental_solutions_stream(cube, tables, Some(20), true);
-        let future = stream.next();
-
-        let _solution = futures::executor::block_on(future).unwrap();
+        let _solution = solve(
+            cube,
+            tables,
+            SolveOptions {
+                max_moves: Some(20),
+                ..Default::default()
+            },
+        )
+        .unwrap();
         // {
         //     print!("{:02} ", solution.len());
-        //     for m in solution.into_iter().rev() {
+        //     for m in solution.moves.into_iter().rev() {
         //         let m = match m {
         //             CubeMove::U1 => CubeMove::U3,
         //             CubeMove::U2 => CubeMove::U2,
@@ -0,0 +1,34 @@
+use three_by_three::{PrunePhase1Table, PrunePhase2Table, TableMapping, Tables};
+
+/// usage: `table_stats [folder] [--huge-pages] [--populate] [--lock]`
+pub fn main() {
+    let (flags, args): (Vec<String>, Vec<String>) = std::env::args()
+        .skip(1)
+        .partition(|arg| arg.starts_with("--"));
+
+    let folder = args
+        .into_iter()
+        .next()
+        .unwrap_or_else(|| "tables".to_string());
+    let mapping = TableMapping {
+        huge_pages: flags.iter().any(|flag| flag == "--huge-pages"),
+        populate: flags.iter().any(|flag| flag == "--populate"),
+        lock: flags.iter().any(|flag| flag == "--lock"),
+    };
+    let tables = Tables::with_mapping(folder, mapping).unwrap();
+
+    println!("table mapping ({mapping:?})");
+    for report in tables.mapping_report() {
+        println!("{report}");
+    }
+    println!();
+
+    let phase_1: &PrunePhase1Table = tables.as_ref();
+    println!("phase 1 pruning table");
+    println!("{}", phase_1.histogram());
+    println!();
+
+    let phase_2: &PrunePhase2Table = tables.as_ref();
+    println!("phase 2 pruning table");
+    println!("{}", phase_2.histogram());
+}
@@ -0,0 +1,309 @@
+use std::{collections::HashSet, str::FromStr};
+
+use super::{
+    cube_axis::Axis, cube_move::CubeMove, cube_sym::CubeSymmetry, notation::parse_moves,
+    repr_cube::ReprCube,
+};
+
+/// A sequence of face turns.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct Algorithm {
+    pub moves: Vec<CubeMove>,
+}
+
+impl Algorithm {
+    pub fn new(moves: Vec<CubeMove>) -> Self {
+        Self { moves }
+    }
+
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    /// the cube the algorithm turns a solved cube into.
+    pub fn cube(&self) -> ReprCube {
+        self.moves
+            .iter()
+            .fold(ReprCube::SOLVED, |cube, &mv| cube.apply_move(mv))
+    }
+
+    /// cancel and merge turns of the same face, including across turns of the opposite face, e.g.
+    /// `R L R` into `R2 L` and `R U U' R'` into nothing.
+    pub fn simplify(&self) -> Self {
+        let mut moves: Vec<CubeMove> = Vec::with_capacity(self.moves.len());
+
+        for &mv in &self.moves {
+            // the turns at the end on the same axis, which commute with `mv`.
+            let same_axis = moves
+                .iter()
+                .rev()
+                .take_while(|prev| **prev as u8 / 6 == mv as u8 / 6)
+                .count();
+            let start = moves.len() - same_axis;
+            match (start..moves.len()).find(|&i| moves[i] as u8 / 3 == mv as u8 / 3) {
+                Some(i) => match merge(moves[i], mv) {
+                    Some(merged) => moves[i] = merged,
+                    None => {
+                        moves.remove(i);
+                    }
+                },
+                None => moves.push(mv),
+            }
+        }
+
+        Self { moves }
+    }
+
+    /// `simplify`, then turns of opposite faces in the order the search generates them: U before
+    /// D, F before B and R before L. two algorithms that only differ by cancellations and by the
+    /// order of commuting turns have the same canonical form.
+    pub fn canonicalize(&self) -> Self {
+        let mut moves = self.simplify().moves;
+        // after simplifying, a run of turns on one axis has at most one turn of each face.
+        let mut start = 0;
+        while start < moves.len() {
+            let axis = moves[start] as u8 / 6;
+            let len = moves[start..]
+                .iter()
+                .take_while(|&&mv| mv as u8 / 6 == axis)
+                .count();
+            moves[start..start + len].sort_by_key(|&mv| mv as u8);
+            start += len;
+        }
+        Self { moves }
+    }
+}
+
+impl Algorithm {
+    /// the algorithm that undoes this one.
+    pub fn inverse(&self) -> Self {
+        let moves = self
+            .moves
+            .iter()
+            .rev()
+            .map(|&mv| inverse_move(mv))
+            .collect();
+        Self { moves }
+    }
+
+    /// the algorithm reflected through the plane between the two faces of `axis`, e.g. `R U R'`
+    /// into `L' U' L` for `Axis::RL`.
+    pub fn mirror(&self, axis: Axis) -> Self {
+        let moves = self
+            .moves
+            .iter()
+            .map(|&mv| {
+                let face = if Axis::of_move(mv) == axis {
+                    (mv as u8 / 3) ^ 1
+                } else {
+                    mv as u8 / 3
+                };
+                inverse_move(unsafe {
+                    core::mem::transmute::<u8, CubeMove>(face * 3 + mv as u8 % 3)
+                })
+            })
+            .collect();
+        Self { moves }
+    }
+
+    /// the algorithm that does to `cube.conjugate(sym)` what this one does to `cube`.
+    pub fn conjugate(&self, sym: CubeSymmetry) -> Self {
+        let moves = self.moves.iter().map(|&mv| mv.conjugate(sym)).collect();
+        Self { moves }
+    }
+}
+
+fn inverse_move(mv: CubeMove) -> CubeMove {
+    unsafe { core::mem::transmute(mv as u8 / 3 * 3 + 2 - mv as u8 % 3) }
+}
+
+// the single turn of a face that does `a` then `b`, which must turn the same face.
+fn merge(a: CubeMove, b: CubeMove) -> Option<CubeMove> {
+    let amount = (a as u8 % 3 + b as u8 % 3 + 2) % 4;
+    (amount != 0).then(|| unsafe { core::mem::transmute(a as u8 / 3 * 3 + amount - 1) })
+}
+
+/// remove the sequences that are equal to an earlier one after `Algorithm::canonicalize`, keeping
+/// the order of the rest.
+pub fn dedup_equivalent<T: AsRef<[CubeMove]>>(sequences: &mut Vec<T>) {
+    let mut seen = HashSet::new();
+    sequences.retain(|sequence| {
+        let algorithm = Algorithm::new(sequence.as_ref().to_vec());
+        seen.insert(algorithm.canonicalize())
+    });
+}
+
+impl AsRef<[CubeMove]> for Algorithm {
+    fn as_ref(&self) -> &[CubeMove] {
+        &self.moves
+    }
+}
+
+impl From<Vec<CubeMove>> for Algorithm {
+    fn from(moves: Vec<CubeMove>) -> Self {
+        Self { moves }
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        Ok(Self::new(parse_moves(s)?))
+    }
+}
+
+impl std::fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, mv) in self.moves.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{mv}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    fn alg(s: &str) -> Algorithm {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn simplify_cancels_and_merges() {
+        assert_eq!(alg("R R'").simplify(), Algorithm::default());
+        assert_eq!(alg("U U2").simplify(), alg("U'"));
+        assert_eq!(alg("R L R").simplify(), alg("R2 L"));
+        assert_eq!(alg("F R U U' R' F'").simplify(), Algorithm::default());
+        assert_eq!(alg("R U D U' R").simplify(), alg("R D R"));
+        assert_eq!(alg("R U R' U'").simplify(), alg("R U R' U'"));
+
+        assert_eq!(alg("D U F B' F2 L R").canonicalize(), alg("U D F' B' R L"));
+        assert_eq!(alg("R L' R'").canonicalize(), alg("L'"));
+        assert_eq!(alg("R U R' U'").to_string(), "R U R' U'");
+    }
+
+    #[test]
+    fn simplify_keeps_the_cube() {
+        let mut rng = ChaCha8Rng::seed_from_u64(7);
+        for _ in 0..500 {
+            // few faces, so that there is a lot to cancel.
+            let len = rng.random_range(0..30);
+            let moves = (0..len)
+                .map(|_| {
+                    let face = [0, 1, 4][rng.random_range(0..3)];
+                    unsafe {
+                        core::mem::transmute::<u8, CubeMove>(face * 3 + rng.random_range(0..3))
+                    }
+                })
+                .collect::<Vec<_>>();
+            let algorithm = Algorithm::new(moves);
+
+            let simplified = algorithm.simplify();
+            let canonical = algorithm.canonicalize();
+            assert_eq!(simplified.cube(), algorithm.cube());
+            assert_eq!(canonical.cube(), algorithm.cube());
+            assert!(canonical.len() <= algorithm.len());
+            assert_eq!(simplified.simplify(), simplified);
+            assert_eq!(canonical.canonicalize(), canonical);
+            assert_eq!(simplified.len(), canonical.len());
+
+            // no two neighbouring turns can be merged.
+            for pair in canonical.moves.windows(2) {
+                assert_ne!(pair[0] as u8 / 3, pair[1] as u8 / 3);
+            }
+        }
+    }
+
+    fn random_algorithm(rng: &mut impl Rng) -> Algorithm {
+        let len = rng.random_range(0..25);
+        let moves = (0..len)
+            .map(|_| unsafe { core::mem::transmute::<u8, CubeMove>(rng.random_range(0..18)) })
+            .collect();
+        Algorithm::new(moves)
+    }
+
+    #[test]
+    fn transformations() {
+        assert_eq!(alg("R U R' U'").inverse(), alg("U R U' R'"));
+        assert_eq!(alg("R U2 F'").inverse(), alg("F U2 R'"));
+        assert_eq!(alg("R U R'").mirror(Axis::RL), alg("L' U' L"));
+        assert_eq!(alg("R U F'").mirror(Axis::FB), alg("R' U' B"));
+        assert_eq!(alg("R U2 D").mirror(Axis::UD), alg("R' D2 U'"));
+
+        // the mirror through each plane is a conjugation by one of the mirror symmetries.
+        let mut rng = ChaCha8Rng::seed_from_u64(11);
+        let algorithms = (0..50)
+            .map(|_| random_algorithm(&mut rng))
+            .collect::<Vec<_>>();
+        for axis in Axis::ALL {
+            assert!(CubeSymmetry::all_iter().any(|sym| {
+                algorithms
+                    .iter()
+                    .all(|a| a.mirror(axis).cube() == a.cube().conjugate(sym))
+            }));
+        }
+        assert_eq!(
+            alg("R U R' U'").mirror(Axis::RL).cube(),
+            alg("R U R' U'").cube().conjugate(CubeSymmetry(1))
+        );
+    }
+
+    #[test]
+    fn transformations_match_cubes() {
+        let mut rng = ChaCha8Rng::seed_from_u64(12);
+        for _ in 0..200 {
+            let algorithm = random_algorithm(&mut rng);
+            let cube = algorithm.cube();
+            let other: ReprCube =
+                rand::distr::Distribution::sample(&rand::distr::StandardUniform, &mut rng);
+
+            assert_eq!(algorithm.inverse().cube(), cube.inverse());
+            assert_eq!(cube.then(cube.inverse()), ReprCube::SOLVED);
+            assert_eq!(cube.inverse().then(cube), ReprCube::SOLVED);
+            assert_eq!(
+                other.then(cube).inverse(),
+                cube.inverse().then(other.inverse())
+            );
+            assert_eq!(other.inverse().inverse(), other);
+
+            for axis in Axis::ALL {
+                let mirrored = algorithm.mirror(axis);
+                assert_eq!(mirrored.mirror(axis), algorithm);
+                assert_eq!(mirrored.inverse(), algorithm.inverse().mirror(axis));
+            }
+
+            let sym = CubeSymmetry(rng.random_range(0..48));
+            let conjugated = algorithm.conjugate(sym);
+            assert_eq!(conjugated.cube(), cube.conjugate(sym));
+            assert_eq!(
+                other.then(cube).conjugate(sym),
+                other.conjugate(sym).then(conjugated.cube())
+            );
+        }
+    }
+
+    #[test]
+    fn dedup() {
+        let mut algorithms = vec![
+            alg("R L U"),
+            alg("L R U"),
+            alg("R U U' L U"),
+            alg("R U"),
+            alg("R U"),
+        ];
+        dedup_equivalent(&mut algorithms);
+        assert_eq!(algorithms, vec![alg("R L U"), alg("R U")]);
+    }
+}
@@ -0,0 +1,104 @@
+use super::{cube_move::CubeMove, cube_sym::CubeSymmetry};
+
+/// One of the three axes of the cube, named by its two faces.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, PartialOrd, Ord)]
+pub enum Axis {
+    UD,
+    FB,
+    RL,
+}
+
+impl Axis {
+    pub const ALL: [Axis; 3] = [Axis::UD, Axis::FB, Axis::RL];
+
+    /// the symmetry that moves this axis onto the UD axis when a cube is conjugated by it.
+    pub const fn to_ud(self) -> CubeSymmetry {
+        match self {
+            Axis::UD => CubeSymmetry(0),
+            Axis::FB => CubeSymmetry(2 << 4),
+            Axis::RL => CubeSymmetry(1 << 4),
+        }
+    }
+
+    /// the symmetry that undoes `to_ud`.
+    pub const fn to_ud_inverse(self) -> CubeSymmetry {
+        match self {
+            Axis::UD => CubeSymmetry(0),
+            Axis::FB => CubeSymmetry(1 << 4),
+            Axis::RL => CubeSymmetry(2 << 4),
+        }
+    }
+
+    /// the symmetry that moves this axis onto the FB axis, the axis `EdgeOrient` is measured
+    /// against.
+    pub const fn to_fb(self) -> CubeSymmetry {
+        match self {
+            Axis::UD => CubeSymmetry(1 << 4),
+            Axis::FB => CubeSymmetry(0),
+            Axis::RL => CubeSymmetry(2 << 4),
+        }
+    }
+
+    pub const fn of_move(mv: CubeMove) -> Self {
+        match mv as u8 / 6 {
+            0 => Axis::UD,
+            1 => Axis::FB,
+            _ => Axis::RL,
+        }
+    }
+
+    /// the quarter turns of the two faces on this axis. these are the moves that flip edges
+    /// relative to the axis.
+    pub const fn quarter_turns(self) -> [CubeMove; 4] {
+        use CubeMove::*;
+        match self {
+            Axis::UD => [U1, U3, D1, D3],
+            Axis::FB => [F1, F3, B1, B3],
+            Axis::RL => [R1, R3, L1, L3],
+        }
+    }
+}
+
+impl std::fmt::Display for Axis {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Axis::UD => "UD",
+            Axis::FB => "FB",
+            Axis::RL => "RL",
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cube_ops::repr_cube::ReprCube;
+
+    use super::*;
+
+    #[test]
+    fn to_ud_moves_the_axis_onto_ud() {
+        for axis in Axis::ALL {
+            let [quarter_turn, ..] = axis.quarter_turns();
+            let cube = ReprCube::SOLVED.apply_move(quarter_turn);
+            let moved = cube.conjugate(axis.to_ud());
+
+            // a quarter turn of U or D keeps every edge and corner oriented.
+            assert_eq!(moved.corner_orient, ReprCube::SOLVED.corner_orient);
+            assert_eq!(moved.edge_orient, ReprCube::SOLVED.edge_orient);
+            assert_eq!(moved.conjugate(axis.to_ud_inverse()), cube);
+        }
+    }
+
+    #[test]
+    fn to_fb_moves_the_axis_onto_fb() {
+        for axis in Axis::ALL {
+            for mv in axis.quarter_turns() {
+                assert!(
+                    Axis::FB
+                        .quarter_turns()
+                        .contains(&mv.conjugate(axis.to_fb()))
+                );
+            }
+        }
+    }
+}
@@ -110,7 +110,7 @@ pub const EDGE_PERM_LOOKUP: [EdgePerm; 48] = {
     table
 };
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 #[repr(transparent)]
 pub struct CubeSymmetry(pub u8);
 
@@ -314,8 +314,50 @@ impl ReprCube {
 }
 
 impl CubeMove {
-    pub const fn conjugate(self, _sym: CubeSymmetry) -> Self {
-        unimplemented!()
+    /// the move `m` such that `cube.apply_move(self).conjugate(sym)` is
+    /// `cube.conjugate(sym).apply_move(m)`.
+    pub const fn conjugate(self, sym: CubeSymmetry) -> Self {
+        const TABLE: [CubeMove; 18 * 48] = const {
+            let mut val = [CubeMove::U1; 18 * 48];
+            let mut i = 0;
+            while i < 18 {
+                let mv: CubeMove = unsafe { core::mem::transmute(i as u8) };
+                let mut j = 0;
+                while j < 48 {
+                    // the corner permutation alone tells the moves apart. conjugate it the same way
+                    // `ReprCube::conjugate` does.
+                    let perm = mv
+                        .into_corner_perm()
+                        .domino_conjugate(DominoSymmetry(j as u8 & 0xF));
+                    let perm = match j >> 4 {
+                        0 => perm,
+                        1 => S_URF3_1_CORNER_PERM
+                            .then(perm)
+                            .then(S_URF3_1_CORNER_PERM)
+                            .then(S_URF3_1_CORNER_PERM),
+                        _ => S_URF3_1_CORNER_PERM
+                            .then(S_URF3_1_CORNER_PERM)
+                            .then(perm)
+                            .then(S_URF3_1_CORNER_PERM),
+                    };
+
+                    let mut k = 0;
+                    while k < 18 {
+                        let candidate: CubeMove = unsafe { core::mem::transmute(k as u8) };
+                        if candidate.into_corner_perm().const_eq(perm) {
+                            val[i * 48 + j] = candidate;
+                            break;
+                        }
+                        k += 1;
+                    }
+                    j += 1;
+                }
+                i += 1;
+            }
+
+            val
+        };
+        TABLE[self.into_index() * 48 + sym.0 as usize]
     }
 
     pub const fn domino_conjugate(self, sym: DominoSymmetry) -> Self {
@@ -380,6 +422,18 @@ fn check_no_duplicates_for_corner_perm_repr() {
     }
 }
 
+#[test]
+fn moves_with_conjugation() {
+    for sym in CubeSymmetry::all_iter() {
+        for mv in CubeMove::all_iter() {
+            assert_eq!(
+                ReprCube::SOLVED.apply_move(mv).conjugate(sym),
+                ReprCube::SOLVED.apply_move(mv.conjugate(sym))
+            );
+        }
+    }
+}
+
 #[test]
 fn random_cubes_with_conjugation() {
     use rand::SeedableRng;
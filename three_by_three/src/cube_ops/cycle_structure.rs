@@ -0,0 +1,190 @@
+use crate::permutation_math::permutation::{Permutation, lcm};
+
+use super::repr_cube::ReprCube;
+
+const CORNER_NAMES: [&str; 8] = ["UFR", "UFL", "UBR", "UBL", "DFR", "DFL", "DBR", "DBL"];
+const EDGE_NAMES: [&str; 12] = [
+    "UF", "UB", "UR", "UL", "DF", "DB", "DR", "DL", "FR", "FL", "BR", "BL",
+];
+
+/// A cycle of pieces, in the order they move, and the orientation they pick up going around it
+/// once.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct PieceCycle {
+    pub slots: Vec<u8>,
+    /// clockwise twists for corners, flips for edges.
+    pub twist: u8,
+}
+
+/// The cycles a cube moves its pieces in, starting from solved. pieces that stay in their slot are
+/// left out unless they are twisted or flipped.
+///
+/// displays in cycle notation, with `+` after a cycle that twists its corners clockwise or flips
+/// its edges and `-` after one that twists its corners counterclockwise, e.g.
+/// `(UFR UBR DBR DFR) (UR BR DR FR)` for `R`.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct CycleStructure {
+    pub corners: Vec<PieceCycle>,
+    pub edges: Vec<PieceCycle>,
+}
+
+impl CycleStructure {
+    /// the smallest number of times the cube has to be repeated to get back to solved.
+    pub fn order(&self) -> u64 {
+        let corners = self.corners.iter().map(|cycle| (cycle, 3));
+        let edges = self.edges.iter().map(|cycle| (cycle, 2));
+        corners
+            .chain(edges)
+            .fold(1, |order, (cycle, orientations)| {
+                let twisted = if cycle.twist == 0 { 1 } else { orientations };
+                lcm(order, cycle.slots.len() as u64 * twisted)
+            })
+    }
+}
+
+impl std::fmt::Display for CycleStructure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let corners = self.corners.iter().map(|cycle| (cycle, &CORNER_NAMES[..]));
+        let edges = self.edges.iter().map(|cycle| (cycle, &EDGE_NAMES[..]));
+        let cycles = corners
+            .chain(edges)
+            .map(|(cycle, names)| {
+                let slots = cycle
+                    .slots
+                    .iter()
+                    .map(|&slot| names[slot as usize])
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                let twist = match (cycle.twist, names.len()) {
+                    (0, _) => "",
+                    (2, 8) => "-",
+                    _ => "+",
+                };
+                format!("({slots}){twist}")
+            })
+            .collect::<Vec<_>>();
+
+        if cycles.is_empty() {
+            f.write_str("solved")
+        } else {
+            f.write_str(&cycles.join(" "))
+        }
+    }
+}
+
+// the cycles of `perm`, and the pieces fixed in place but misoriented.
+fn piece_cycles<const N: usize>(
+    perm: Permutation<N>,
+    orient: [u8; N],
+    orientations: u8,
+) -> Vec<PieceCycle> {
+    let mut cycles = perm
+        .cycles()
+        .into_iter()
+        .chain(
+            (0..N as u8)
+                .filter(|&slot| perm.0[slot as usize] == slot)
+                .map(|slot| vec![slot]),
+        )
+        .map(|slots| {
+            let twist = slots.iter().map(|&slot| orient[slot as usize]).sum::<u8>() % orientations;
+            PieceCycle { slots, twist }
+        })
+        .filter(|cycle| cycle.slots.len() > 1 || cycle.twist != 0)
+        .collect::<Vec<_>>();
+    cycles.sort_by_key(|cycle| cycle.slots[0]);
+    cycles
+}
+
+impl ReprCube {
+    pub fn cycle_structure(self) -> CycleStructure {
+        CycleStructure {
+            corners: piece_cycles(self.corner_perm.0, self.corner_orient.0, 3),
+            edges: piece_cycles(self.edge_perm.0, self.edge_orient.0, 2),
+        }
+    }
+
+    /// the smallest number of times the cube has to be repeated to get back to solved, counting
+    /// the twists and flips a cycle leaves behind.
+    pub fn order(self) -> u64 {
+        self.cycle_structure().order()
+    }
+
+    /// the cube repeated `k` times.
+    pub const fn pow(self, mut k: u32) -> Self {
+        let mut out = Self::SOLVED;
+        let mut square = self;
+        while k > 0 {
+            if k & 1 == 1 {
+                out = out.then(square);
+            }
+            square = square.then(square);
+            k >>= 1;
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{cube, cube_ops::partial_reprs::corner_orient::CornerOrient};
+
+    use super::*;
+
+    #[test]
+    fn orders() {
+        assert_eq!(ReprCube::SOLVED.order(), 1);
+        assert_eq!(cube![R].order(), 4);
+        assert_eq!(cube![R U Rp Up].order(), 6);
+        assert_eq!(cube![R U].order(), 105);
+        assert_eq!(cube![R U Rp U R U2 Rp].order(), 6);
+        assert_eq!(cube![R U2 Dp B Dp].order(), 1260);
+
+        for cube in [
+            cube![R U],
+            cube![R U Rp Up],
+            cube![R U2 Dp B Dp],
+            cube![F R Up L2 D B],
+        ] {
+            let order = cube.order();
+            assert_eq!(cube.pow(order as u32), ReprCube::SOLVED);
+            // no smaller repetition solves the cube.
+            for prime in [2, 3, 5, 7, 11] {
+                if order % prime == 0 {
+                    assert_ne!(cube.pow((order / prime) as u32), ReprCube::SOLVED);
+                }
+            }
+
+            // the order of the permutation alone can be smaller.
+            assert_eq!(order % cube.corner_perm.0.order(), 0);
+            assert_eq!(order % cube.edge_perm.0.order(), 0);
+        }
+
+        let mut repeated = ReprCube::SOLVED;
+        for k in 0..6 {
+            assert_eq!(cube![R U].pow(k), repeated);
+            repeated = repeated.then(cube![R U]);
+        }
+    }
+
+    #[test]
+    fn display_cycles() {
+        assert_eq!(ReprCube::SOLVED.cycle_structure().to_string(), "solved");
+        assert_eq!(
+            cube![R].cycle_structure().to_string(),
+            "(UFR UBR DBR DFR) (UR BR DR FR)"
+        );
+        assert_eq!(
+            cube![F].cycle_structure().to_string(),
+            "(UFR DFR DFL UFL) (UF FR DF FL)"
+        );
+        assert_eq!(cube![U2].cycle_structure().corners.len(), 2);
+
+        let twisted = ReprCube {
+            corner_orient: CornerOrient([1, 2, 0, 0, 0, 0, 0, 0]),
+            ..ReprCube::SOLVED
+        };
+        assert_eq!(twisted.cycle_structure().to_string(), "(UFR)+ (UFL)-");
+        assert_eq!(twisted.order(), 3);
+    }
+}
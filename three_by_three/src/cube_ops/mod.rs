@@ -1,5 +1,9 @@
+pub mod algorithm;
+pub mod cube_axis;
 pub mod cube_move;
 pub mod cube_prev_axis;
 pub mod cube_sym;
+pub mod cycle_structure;
+pub mod notation;
 pub mod partial_reprs;
 pub mod repr_cube;
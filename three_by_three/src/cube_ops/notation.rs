@@ -0,0 +1,321 @@
+use std::str::FromStr;
+
+use anyhow::anyhow;
+
+use super::cube_move::CubeMove;
+
+// faces in the order of `CubeMove`.
+const U: usize = 0;
+const D: usize = 1;
+const F: usize = 2;
+const B: usize = 3;
+const R: usize = 4;
+const L: usize = 5;
+
+impl FromStr for CubeMove {
+    type Err = anyhow::Error;
+
+    /// a single face turn, like `R`, `R2` or `R'`.
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let mut chars = s.chars();
+        let face = chars
+            .next()
+            .and_then(|c| "UDFBRL".find(c))
+            .ok_or_else(|| anyhow!("{s:?} is not a face turn"))?;
+        let amount =
+            parse_amount(chars.as_str()).ok_or_else(|| anyhow!("{s:?} is not a face turn"))?;
+        Ok(face_turn(face, amount))
+    }
+}
+
+/// parse a sequence of moves in the usual notation, e.g. `R U R' U'` or `(r U R' U') M2`.
+///
+/// wide turns (`r` or `Rw`), slices (`M`, `E`, `S`) and rotations (`x`, `y`, `z`) are turned into
+/// the face turns that move the pieces the same way relative to the centers. a rotation left over
+/// at the end is dropped, since `ReprCube` keeps the centers fixed.
+///
+/// commutators `[A, B]` expand into `A B A' B'` and conjugates `[C: A]` into `C A C'`, nested in
+/// any way, e.g. `[F: [R U R', D]]`. the inverted parts undo rotations, wide turns and slices too,
+/// so `[x: R]` is `x R x'` and `[M', U2]` is `M' U2 M U2`.
+pub fn parse_moves(s: &str) -> anyhow::Result<Vec<CubeMove>> {
+    let mut parser = Parser { s, pos: 0 };
+    let tokens = parser.sequence()?;
+    match s[parser.pos..].chars().next() {
+        Some(c) => Err(anyhow!("unexpected {c:?} in {s:?}")),
+        None => face_turns(&tokens),
+    }
+}
+
+// a move in the notation, e.g. `Rw'` as the name `Rw` and 3 quarter turns.
+#[derive(Clone, Copy)]
+struct Token<'a> {
+    name: &'a str,
+    amount: u8,
+}
+
+impl Token<'_> {
+    fn inverse(self) -> Self {
+        Self {
+            amount: 4 - self.amount,
+            ..self
+        }
+    }
+}
+
+struct Parser<'a> {
+    s: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    // moves up to the end or a `,`, `:` or `]` that closes a bracket.
+    fn sequence(&mut self) -> anyhow::Result<Vec<Token<'a>>> {
+        let mut moves = Vec::new();
+        let mut start = self.pos;
+
+        while let Some(c) = self.s[self.pos..].chars().next() {
+            match c {
+                '[' => {
+                    moves.extend(tokens(&self.s[start..self.pos])?);
+                    self.pos += 1;
+                    moves.extend(self.bracket()?);
+                    start = self.pos;
+                }
+                ',' | ':' | ']' => break,
+                _ => self.pos += c.len_utf8(),
+            }
+        }
+
+        moves.extend(tokens(&self.s[start..self.pos])?);
+        Ok(moves)
+    }
+
+    // the inside of a bracket and its closing `]`.
+    fn bracket(&mut self) -> anyhow::Result<Vec<Token<'a>>> {
+        let a = self.sequence()?;
+        let separator = match self.s[self.pos..].chars().next() {
+            Some(separator @ (',' | ':')) => separator,
+            Some(_) => return Err(anyhow!("a bracket needs a `,` or a `:` in {:?}", self.s)),
+            None => return Err(anyhow!("unclosed bracket in {:?}", self.s)),
+        };
+        self.pos += 1;
+        let b = self.sequence()?;
+        if !self.s[self.pos..].starts_with(']') {
+            return Err(anyhow!("unclosed bracket in {:?}", self.s));
+        }
+        self.pos += 1;
+
+        let mut moves = a.clone();
+        moves.extend(&b);
+        moves.extend(a.iter().rev().map(|token| token.inverse()));
+        if separator == ',' {
+            moves.extend(b.iter().rev().map(|token| token.inverse()));
+        }
+        Ok(moves)
+    }
+}
+
+// the moves of a sequence without brackets.
+fn tokens(s: &str) -> anyhow::Result<Vec<Token<'_>>> {
+    s.split(|c: char| c.is_whitespace() || c == '(' || c == ')')
+        .filter(|token| !token.is_empty())
+        .map(|token| {
+            let letter_len = token.chars().next().unwrap().len_utf8();
+            let name_len = if token[letter_len..].starts_with('w') {
+                letter_len + 1
+            } else {
+                letter_len
+            };
+            let amount = parse_amount(&token[name_len..])
+                .ok_or_else(|| anyhow!("can't parse move {token:?}"))?;
+            Ok(Token {
+                name: &token[..name_len],
+                amount,
+            })
+        })
+        .collect()
+}
+
+// the face turns of `tokens`, with one frame for the whole sequence.
+fn face_turns(tokens: &[Token]) -> anyhow::Result<Vec<CubeMove>> {
+    // the face each face of the notation is on, after the rotations so far.
+    let mut frame = [U, D, F, B, R, L];
+    let mut moves = Vec::new();
+
+    for &Token { name, amount } in tokens {
+        let mut chars = name.chars();
+        let letter = chars.next().unwrap();
+        let wide = chars.next().is_some();
+        let inverse = 4 - amount;
+
+        let mut turn = |face: usize, amount: u8| moves.push(face_turn(frame[face], amount));
+        match (letter, wide) {
+            ('U' | 'D' | 'F' | 'B' | 'R' | 'L', false) => {
+                turn("UDFBRL".find(letter).unwrap(), amount)
+            }
+            // a wide turn is the opposite face turn, then a rotation.
+            ('r', _) | ('R', true) => {
+                turn(L, amount);
+                rotate(&mut frame, 'x', amount);
+            }
+            ('l', _) | ('L', true) => {
+                turn(R, amount);
+                rotate(&mut frame, 'x', inverse);
+            }
+            ('u', _) | ('U', true) => {
+                turn(D, amount);
+                rotate(&mut frame, 'y', amount);
+            }
+            ('d', _) | ('D', true) => {
+                turn(U, amount);
+                rotate(&mut frame, 'y', inverse);
+            }
+            ('f', _) | ('F', true) => {
+                turn(B, amount);
+                rotate(&mut frame, 'z', amount);
+            }
+            ('b', _) | ('B', true) => {
+                turn(F, amount);
+                rotate(&mut frame, 'z', inverse);
+            }
+            // a slice is both face turns on its axis, then a rotation.
+            ('M', false) => {
+                turn(R, amount);
+                turn(L, inverse);
+                rotate(&mut frame, 'x', inverse);
+            }
+            ('E', false) => {
+                turn(U, amount);
+                turn(D, inverse);
+                rotate(&mut frame, 'y', inverse);
+            }
+            ('S', false) => {
+                turn(F, inverse);
+                turn(B, amount);
+                rotate(&mut frame, 'z', amount);
+            }
+            ('x' | 'y' | 'z', false) => rotate(&mut frame, letter, amount),
+            _ => return Err(anyhow!("can't parse move {name:?}")),
+        }
+    }
+
+    Ok(moves)
+}
+
+// "" is a quarter turn, "2" a half turn and "'" a counterclockwise quarter turn.
+fn parse_amount(s: &str) -> Option<u8> {
+    match s {
+        "" => Some(1),
+        "2" | "2'" => Some(2),
+        "'" | "3" => Some(3),
+        _ => None,
+    }
+}
+
+fn face_turn(face: usize, amount: u8) -> CubeMove {
+    unsafe { core::mem::transmute((face * 3) as u8 + amount - 1) }
+}
+
+// turn the whole cube `amount` quarter turns in the direction of R for x, U for y and F for z.
+fn rotate(frame: &mut [usize; 6], axis: char, amount: u8) {
+    // each face takes the place of the next one in the cycle.
+    let cycle = match axis {
+        'x' => [U, F, D, B],
+        'y' => [F, R, B, L],
+        _ => [U, L, D, R],
+    };
+    for _ in 0..amount {
+        let first = frame[cycle[0]];
+        for i in 0..3 {
+            frame[cycle[i]] = frame[cycle[i + 1]];
+        }
+        frame[cycle[3]] = first;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cube_ops::repr_cube::ReprCube;
+
+    use super::*;
+
+    fn apply(moves: &[CubeMove]) -> ReprCube {
+        moves
+            .iter()
+            .fold(ReprCube::SOLVED, |cube, &mv| cube.apply_move(mv))
+    }
+
+    #[test]
+    fn parse_notation() -> anyhow::Result<()> {
+        use CubeMove::*;
+
+        assert_eq!(parse_moves("R U R' U'")?, vec![R1, U1, R3, U3]);
+        assert_eq!(parse_moves("(F2 D2') B3")?, vec![F2, D2, B3]);
+        assert_eq!("L'".parse::<CubeMove>()?, L3);
+        assert!("r".parse::<CubeMove>().is_err());
+
+        // rotations move the faces the notation refers to.
+        assert_eq!(parse_moves("x U x'")?, vec![F1]);
+        assert_eq!(parse_moves("z U z'")?, vec![L1]);
+        assert_eq!(parse_moves("y R U R' U' y'")?, vec![B1, U1, B3, U3]);
+        assert_eq!(parse_moves("x y z")?, vec![]);
+
+        // wide turns and slices leave the pieces where the layer turns would.
+        assert_eq!(
+            apply(&parse_moves("r U r'")?),
+            apply(&parse_moves("Rw U Rw'")?)
+        );
+        assert_eq!(
+            apply(&parse_moves("M2 U M2 U2 M2 U M2")?).corner_perm,
+            ReprCube::SOLVED.corner_perm
+        );
+        assert_eq!(apply(&parse_moves("r R'")?), apply(&parse_moves("M'")?));
+        assert_eq!(apply(&parse_moves("u U'")?), apply(&parse_moves("E'")?));
+        assert_eq!(apply(&parse_moves("f F'")?), apply(&parse_moves("S")?));
+        for (wide, face) in [
+            ("r", "R"),
+            ("l", "L"),
+            ("u", "U"),
+            ("d", "D"),
+            ("f", "F"),
+            ("b", "B"),
+        ] {
+            let sequence = format!("{wide} {wide} {wide} {wide}");
+            assert_eq!(apply(&parse_moves(&sequence)?), ReprCube::SOLVED, "{face}");
+        }
+
+        // commutators and conjugates.
+        assert_eq!(parse_moves("[R, U]")?, vec![R1, U1, R3, U3]);
+        assert_eq!(
+            parse_moves("[R U R', D]")?,
+            parse_moves("R U R' D R U' R' D'")?
+        );
+        assert_eq!(
+            parse_moves("[F: [R U R', D2]] U")?,
+            parse_moves("F R U R' D2 R U' R' D2 F' U")?
+        );
+        assert_eq!(parse_moves("R2 [U: R]")?, vec![R2, U1, R1, U3]);
+        assert!(parse_moves("[R, U").is_err());
+        assert!(parse_moves("[").is_err());
+        assert!(parse_moves("[R U").is_err());
+        assert!(parse_moves("[R U]").is_err());
+        assert!(parse_moves("R] U").is_err());
+
+        // rotations and slices inside a bracket are undone with the rest of it.
+        assert_eq!(parse_moves("[x: R]")?, parse_moves("x R x'")?);
+        assert_eq!(parse_moves("[x: U]")?, vec![F1]);
+        assert_eq!(parse_moves("[x: [R, U]]")?, parse_moves("x R U R' U' x'")?);
+        assert_eq!(parse_moves("[r: U]")?, parse_moves("r U r'")?);
+        let edge_cycle = parse_moves("[M', U2]")?;
+        assert_eq!(edge_cycle, parse_moves("M' U2 M U2")?);
+        let cycles = apply(&edge_cycle).cycle_structure();
+        assert!(cycles.corners.is_empty());
+        assert_eq!(cycles.edges.len(), 1);
+        assert_eq!(cycles.edges[0].slots.len(), 3);
+
+        assert!(parse_moves("R Q").is_err());
+        assert!(parse_moves("R4").is_err());
+
+        Ok(())
+    }
+}
@@ -64,6 +64,19 @@ impl ReprCube {
         }
     }
 
+    /// the cube that undoes `self`, so that `self.then(self.inverse())` is solved.
+    pub const fn inverse(self) -> Self {
+        let corner_perm = self.corner_perm.inverse();
+        let edge_perm = self.edge_perm.inverse();
+        Self {
+            corner_perm,
+            corner_orient: CornerOrient::SOLVED.uncorrect(self.corner_orient.permute(corner_perm)),
+            edge_perm,
+            // a flip undoes itself.
+            edge_orient: self.edge_orient.permute(edge_perm),
+        }
+    }
+
     pub const fn const_eq(self, other: Self) -> bool {
         self.corner_perm.const_eq(other.corner_perm)
             && self.corner_orient.const_eq(other.corner_orient)
@@ -71,6 +84,34 @@ impl ReprCube {
             && self.edge_orient.const_eq(other.edge_orient)
     }
 
+    /// check that the cube can be reached from solved by turning faces.
+    pub fn validate(self) -> Result<(), InvalidCube> {
+        let corner_perm = Permutation::try_const_from_array(self.corner_perm.0.0)
+            .map_err(|_| InvalidCube::CornerPerm)?;
+        let edge_perm = Permutation::try_const_from_array(self.edge_perm.0.0)
+            .map_err(|_| InvalidCube::EdgePerm)?;
+
+        let corner_orient = self.corner_orient.0;
+        if corner_orient.iter().any(|&o| o >= 3)
+            || corner_orient.iter().map(|&o| o as u32).sum::<u32>() % 3 != 0
+        {
+            return Err(InvalidCube::CornerOrient);
+        }
+
+        let edge_orient = self.edge_orient.0;
+        if edge_orient.iter().any(|&o| o >= 2)
+            || edge_orient.iter().map(|&o| o as u32).sum::<u32>() % 2 != 0
+        {
+            return Err(InvalidCube::EdgeOrient);
+        }
+
+        if corner_perm.is_odd() != edge_perm.is_odd() {
+            return Err(InvalidCube::Parity);
+        }
+
+        Ok(())
+    }
+
     pub fn pretty_print(self) {
         //-> [[&'static str; 9]; 6] {
         const COLOR_CHARS: [&str; 6] = [
@@ -186,6 +227,35 @@ impl ReprCube {
     }
 }
 
+/// Why a `ReprCube` can't be reached from solved.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum InvalidCube {
+    /// the corner permutation isn't a permutation.
+    CornerPerm,
+    /// the edge permutation isn't a permutation.
+    EdgePerm,
+    /// a corner has an orientation above 2, or the corners are twisted.
+    CornerOrient,
+    /// an edge has an orientation above 1, or an edge is flipped.
+    EdgeOrient,
+    /// exactly one of the corner and edge permutations is odd.
+    Parity,
+}
+
+impl std::fmt::Display for InvalidCube {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            InvalidCube::CornerPerm => "the corner permutation is not a permutation",
+            InvalidCube::EdgePerm => "the edge permutation is not a permutation",
+            InvalidCube::CornerOrient => "the corner orientation is invalid",
+            InvalidCube::EdgeOrient => "the edge orientation is invalid",
+            InvalidCube::Parity => "the corner and edge permutations have different parities",
+        })
+    }
+}
+
+impl std::error::Error for InvalidCube {}
+
 #[macro_export]
 macro_rules! cube {
     // 1) ENTRY POINT: invoked as `cube![ F U2 Dp ]`
@@ -256,3 +326,24 @@ fn do_some_moves() {
 fn do_some_other_moves() {
     cube![R U Rp Up].pretty_print();
 }
+
+#[test]
+fn validate_rejects_unreachable_cubes() {
+    assert_eq!(cube![R U Rp Up F2].validate(), Ok(()));
+
+    let mut twisted = cube![R U Rp Up];
+    twisted.corner_orient.0[0] = (twisted.corner_orient.0[0] + 1) % 3;
+    assert_eq!(twisted.validate(), Err(InvalidCube::CornerOrient));
+
+    let mut flipped = ReprCube::SOLVED;
+    flipped.edge_orient.0[3] = 1;
+    assert_eq!(flipped.validate(), Err(InvalidCube::EdgeOrient));
+
+    let mut swapped = ReprCube::SOLVED;
+    swapped.edge_perm.0.0.swap(0, 1);
+    assert_eq!(swapped.validate(), Err(InvalidCube::Parity));
+
+    let mut repeated = ReprCube::SOLVED;
+    repeated.corner_perm.0.0[0] = 1;
+    assert_eq!(repeated.validate(), Err(InvalidCube::CornerPerm));
+}
@@ -0,0 +1,324 @@
+use std::{
+    sync::{
+        Mutex,
+        atomic::{AtomicBool, AtomicU8, Ordering},
+    },
+    time::Instant,
+};
+
+use rayon::iter::ParallelIterator;
+
+use crate::{
+    cube_ops::{cube_move::CubeMove, repr_cube::ReprCube},
+    kociemba::{
+        search::{
+            phase_1_node::TableOffsets,
+            search_counters::SearchCounters,
+            solution::Solution,
+            solve_with_fixed_len_phase_1::{produce_solutions, produce_solutions_par},
+        },
+        tables::Tables,
+    },
+};
+
+/// The longest phase 1 the search is compiled for.
+///
+/// Every phase 1 length is a separate monomorphization (the node stack is a const sized array), so
+/// this is the length of the list in `dispatch_phase_1_len`. Searches can be limited below it at
+/// runtime.
+pub const MAX_PHASE_1_LEN: usize = 20;
+
+/// A search that runs with a fixed phase 1 length.
+///
+/// `N` is the phase 1 length, and `CAP` the node stack capacity it needs, `N * 15 + 4`.
+pub trait FixedPhase1Len {
+    type Output;
+
+    fn run<const N: usize, const CAP: usize>(self) -> Self::Output;
+}
+
+macro_rules! dispatch {
+    ($len:expr, $search:expr, [$($n:literal)*]) => {
+        match $len {
+            $($n => Some($search.run::<$n, { $n * 15 + 4 }>()),)*
+            _ => None,
+        }
+    };
+}
+
+/// run `search` with a phase 1 length of `len`. returns `None` if `len` is above `MAX_PHASE_1_LEN`.
+pub fn dispatch_phase_1_len<S: FixedPhase1Len>(len: usize, search: S) -> Option<S::Output> {
+    dispatch!(
+        len,
+        search,
+        [0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16 17 18 19 20]
+    )
+}
+
+/// The state shared by every phase 1 length of one search.
+pub struct DepthDriver<'a> {
+    pub cube: ReprCube,
+    pub tables: &'a Tables,
+    pub cancel: &'a AtomicBool,
+    /// one more than the longest solution still worth reporting.
+    pub best: &'a AtomicU8,
+    pub counters: &'a SearchCounters,
+    /// the longest phase 1 to try, capped at `MAX_PHASE_1_LEN`.
+    pub max_phase_1_len: usize,
+}
+
+impl<'a> DepthDriver<'a> {
+    /// try every phase 1 length in turn, sending each improving solution to `on_solution`.
+    ///
+    /// stops once no longer phase 1 could improve on the best solution, or when cancelled. returns
+    /// whether the search ran to completion, so that the last solution sent is optimal.
+    pub fn run(&self, parallel: bool, on_solution: impl Fn(Solution) + Sync) -> bool {
+        let start = Instant::now();
+
+        if self.cube == ReprCube::SOLVED {
+            on_solution(Solution {
+                moves: Vec::new(),
+                phase_1_len: 0,
+                elapsed: start.elapsed(),
+                nodes_visited: 0,
+                proven_optimal: true,
+            });
+            return true;
+        }
+
+        let table_offsets = TableOffsets::new(self.tables);
+        let best_len = Mutex::new(None::<usize>);
+        let emit = |moves: Vec<CubeMove>, phase_1_len: usize| {
+            let solution = Solution {
+                moves,
+                phase_1_len,
+                elapsed: start.elapsed(),
+                nodes_visited: self.counters.nodes_visited(),
+                proven_optimal: false,
+            };
+            let mut best_len = best_len.lock().unwrap();
+            if best_len.is_none_or(|best| solution.len() < best) {
+                *best_len = Some(solution.len());
+            }
+            drop(best_len);
+            on_solution(solution);
+        };
+
+        let max_phase_1_len = self.max_phase_1_len.min(MAX_PHASE_1_LEN);
+        let mut reached_limit = true;
+
+        for len in 1..=max_phase_1_len {
+            // a phase 1 of length 1 can finish the solve by itself, longer ones need to leave room for
+            // a phase 2 move, or they would have been found at a shorter length.
+            let shortest_new = if len == 1 { 1 } else { len as u8 + 1 };
+            if self.best.load(Ordering::Acquire) <= shortest_new {
+                reached_limit = false;
+                break;
+            }
+            if self.cancel.load(Ordering::Acquire) {
+                return false;
+            }
+
+            self.counters.set_phase_1_depth(len);
+            if parallel {
+                dispatch_phase_1_len(
+                    len,
+                    Parallel {
+                        driver: self,
+                        table_offsets: &table_offsets,
+                        emit: &emit,
+                    },
+                );
+            } else {
+                dispatch_phase_1_len(
+                    len,
+                    Single {
+                        driver: self,
+                        table_offsets: &table_offsets,
+                        emit: &emit,
+                    },
+                );
+            }
+        }
+
+        if self.cancel.load(Ordering::Acquire) {
+            return false;
+        }
+
+        // when the phase 1 limit was reached, shorter solutions with a longer phase 1 are still
+        // possible.
+        best_len
+            .into_inner()
+            .unwrap()
+            .is_some_and(|len| !reached_limit || len <= max_phase_1_len + 1)
+    }
+}
+
+struct Single<'d, 'a, F> {
+    driver: &'d DepthDriver<'a>,
+    table_offsets: &'d TableOffsets<'d>,
+    emit: &'d F,
+}
+
+impl<F: Fn(Vec<CubeMove>, usize)> FixedPhase1Len for Single<'_, '_, F> {
+    type Output = ();
+
+    fn run<const N: usize, const CAP: usize>(self) {
+        let DepthDriver {
+            cube,
+            tables,
+            best,
+            counters,
+            ..
+        } = *self.driver;
+
+        produce_solutions::<N, CAP>(
+            cube,
+            best.load(Ordering::Acquire),
+            tables,
+            self.table_offsets,
+            counters,
+        )
+        .for_each(|solution| {
+            best.store((solution.len() - 1) as u8, Ordering::Release);
+            (self.emit)(solution, N);
+        });
+    }
+}
+
+struct Parallel<'d, 'a, F> {
+    driver: &'d DepthDriver<'a>,
+    table_offsets: &'d TableOffsets<'d>,
+    emit: &'d F,
+}
+
+impl<F: Fn(Vec<CubeMove>, usize) + Sync> FixedPhase1Len for Parallel<'_, '_, F> {
+    type Output = ();
+
+    fn run<const N: usize, const CAP: usize>(self) {
+        let DepthDriver {
+            cube,
+            tables,
+            best,
+            cancel,
+            counters,
+            ..
+        } = *self.driver;
+
+        produce_solutions_par::<N, CAP>(cube, best, tables, self.table_offsets, cancel, counters)
+            .for_each(|solution| (self.emit)(solution, N));
+    }
+}
+
+/// the first solution found with a phase 1 of exactly `N` moves.
+pub struct FirstSolution<'a> {
+    pub cube: ReprCube,
+    pub tables: &'a Tables,
+    pub table_offsets: &'a TableOffsets<'a>,
+}
+
+impl FixedPhase1Len for FirstSolution<'_> {
+    type Output = Option<Vec<CubeMove>>;
+
+    fn run<const N: usize, const CAP: usize>(self) -> Self::Output {
+        let counters = SearchCounters::default();
+        produce_solutions::<N, CAP>(
+            self.cube,
+            u8::MAX,
+            self.tables,
+            self.table_offsets,
+            &counters,
+        )
+        .next()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Mutex;
+
+    use crate::cube;
+
+    use super::*;
+
+    struct Lengths;
+
+    impl FixedPhase1Len for Lengths {
+        type Output = (usize, usize);
+
+        fn run<const N: usize, const CAP: usize>(self) -> Self::Output {
+            (N, CAP)
+        }
+    }
+
+    #[test]
+    fn dispatches_every_length() {
+        for len in 0..=MAX_PHASE_1_LEN {
+            assert_eq!(
+                dispatch_phase_1_len(len, Lengths),
+                Some((len, len * 15 + 4))
+            );
+        }
+        assert_eq!(dispatch_phase_1_len(MAX_PHASE_1_LEN + 1, Lengths), None);
+    }
+
+    fn run_driver(
+        cube: ReprCube,
+        tables: &Tables,
+        max_phase_1_len: usize,
+        parallel: bool,
+    ) -> Vec<Solution> {
+        let cancel = AtomicBool::new(false);
+        let best = AtomicU8::new(u8::MAX);
+        let counters = SearchCounters::default();
+        let solutions = Mutex::new(Vec::new());
+
+        DepthDriver {
+            cube,
+            tables,
+            cancel: &cancel,
+            best: &best,
+            counters: &counters,
+            max_phase_1_len,
+        }
+        .run(parallel, |solution| {
+            solutions.lock().unwrap().push(solution)
+        });
+
+        solutions.into_inner().unwrap()
+    }
+
+    #[test]
+    fn single_and_parallel_agree() -> anyhow::Result<()> {
+        let tables = Tables::new("tables")?;
+        let cube = cube![R U Rp Up F2 D L2 B Rp D2];
+
+        let single = run_driver(cube, &tables, MAX_PHASE_1_LEN, false);
+        let parallel = run_driver(cube, &tables, MAX_PHASE_1_LEN, true);
+
+        for solution in single.iter().chain(&parallel) {
+            let solved = solution
+                .moves
+                .iter()
+                .fold(cube, |cube, &cube_move| cube.apply_cube_move(cube_move));
+            assert_eq!(solved, ReprCube::SOLVED);
+        }
+
+        let shortest = |solutions: &[Solution]| solutions.iter().map(Solution::len).min();
+        assert_eq!(shortest(&single), Some(10));
+        assert_eq!(shortest(&parallel), Some(10));
+
+        Ok(())
+    }
+
+    #[test]
+    fn max_phase_1_len_limits_search() -> anyhow::Result<()> {
+        let tables = Tables::new("tables")?;
+        let cube = cube![R U Rp Up F2 D L2 B Rp D2];
+
+        assert!(run_driver(cube, &tables, 0, false).is_empty());
+        assert!(!run_driver(cube, &tables, 12, false).is_empty());
+
+        Ok(())
+    }
+}
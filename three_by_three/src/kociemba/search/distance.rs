@@ -0,0 +1,70 @@
+use crate::{
+    cube_ops::{cube_sym::CubeSymmetry, repr_cube::ReprCube},
+    kociemba::{search::phase_1_node::Phase1Node, tables::Tables},
+};
+
+impl Tables {
+    /// the exact number of moves from `cube` to domino reduction, `<U, D, F2, B2, R2, L2>`.
+    pub fn domino_distance(&self, cube: ReprCube) -> u8 {
+        Phase1Node::from_cube(cube, self).distance_heuristic(self)
+    }
+
+    /// whether `cube` is in `<U, D, F2, B2, R2, L2>`.
+    pub fn is_domino_reduced(&self, cube: ReprCube) -> bool {
+        Phase1Node::from_cube(cube, self).is_domino_reduced()
+    }
+
+    /// a lower bound on the length of an optimal solution of `cube`.
+    ///
+    /// every solution passes through domino reduction on all three axes, so this is the largest of
+    /// the three domino distances.
+    pub fn lower_bound(&self, cube: ReprCube) -> u8 {
+        (0..3)
+            .map(|x| self.domino_distance(cube.conjugate(CubeSymmetry(x << 4))))
+            .max()
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use crate::{CubeMove, cube};
+
+    use super::*;
+
+    #[test]
+    fn distances() -> anyhow::Result<()> {
+        let tables = Tables::new("tables")?;
+
+        assert_eq!(tables.lower_bound(ReprCube::SOLVED), 0);
+        assert!(tables.is_domino_reduced(cube![U R2 F2 Dp L2]));
+        assert!(!tables.is_domino_reduced(cube![U R]));
+        assert_eq!(tables.domino_distance(cube![U R2 F2 Dp L2]), 0);
+        assert_eq!(tables.domino_distance(cube![U R2 Dp L2 F]), 1);
+
+        // half turns are in every axis' domino group.
+        assert_eq!(tables.lower_bound(cube![R2]), 0);
+        assert_eq!(tables.lower_bound(cube![U]), 1);
+        for mv in CubeMove::all_iter() {
+            assert!(tables.lower_bound(ReprCube::SOLVED.apply_cube_move(mv)) <= 1);
+        }
+
+        let cube = cube![R U Rp Up F2 D L2 B Rp D2];
+        assert!(tables.domino_distance(cube) <= tables.lower_bound(cube));
+        assert!(tables.lower_bound(cube) <= 10);
+
+        // no solution can be shorter than the lower bound.
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        for _ in 0..20 {
+            let cube: ReprCube =
+                rand::distr::Distribution::sample(&rand::distr::StandardUniform, &mut rng);
+            let solution = crate::kociemba::search::stream_search::solve_direct(cube, &tables);
+            assert!(tables.lower_bound(cube) as usize <= solution.len());
+        }
+
+        Ok(())
+    }
+}
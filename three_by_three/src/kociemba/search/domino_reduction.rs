@@ -0,0 +1,255 @@
+use std::iter;
+
+use crate::{
+    cube_ops::{cube_axis::Axis, cube_move::CubeMove, repr_cube::ReprCube},
+    kociemba::{
+        search::{
+            depth_driver::{FixedPhase1Len, MAX_PHASE_1_LEN, dispatch_phase_1_len},
+            domino_reduction_iter::ud_domino_reductions,
+            move_resolver::move_resolver,
+            phase_1_node::TableOffsets,
+            search_counters::SearchCounters,
+        },
+        tables::Tables,
+    },
+};
+
+/// A move sequence that brings a cube into the domino group of `axis`, e.g. `<U, D, F2, B2, R2, L2>`
+/// for the UD axis.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct DominoReduction {
+    pub axis: Axis,
+    pub moves: Vec<CubeMove>,
+}
+
+/// Options for `domino_reductions`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DominoReductionOptions {
+    /// the axis to reduce to, or `None` for all three.
+    pub axis: Option<Axis>,
+    pub min_len: usize,
+    /// capped at `MAX_PHASE_1_LEN`.
+    pub max_len: usize,
+    /// only keep reductions that don't flip edges relative to this axis, for a reduction that
+    /// follows an EO step on it.
+    pub preserve_eo: Option<Axis>,
+    /// stop after this many reductions.
+    pub max_results: usize,
+}
+
+impl Default for DominoReductionOptions {
+    fn default() -> Self {
+        Self {
+            axis: None,
+            min_len: 0,
+            max_len: 12,
+            preserve_eo: None,
+            max_results: 1000,
+        }
+    }
+}
+
+/// every way to reach domino reduction from `cube` within the options' length bounds, shortest
+/// first.
+///
+/// sequences are canonical: moves on the same axis are merged and ordered, and the last move is a
+/// quarter turn off the target axis, since any other last move would leave a shorter reduction.
+pub fn domino_reductions(
+    cube: ReprCube,
+    tables: &Tables,
+    options: &DominoReductionOptions,
+) -> Vec<DominoReduction> {
+    let table_offsets = TableOffsets::new(tables);
+    let axes = match options.axis {
+        Some(axis) => vec![axis],
+        None => Axis::ALL.to_vec(),
+    };
+    let mut reductions = Vec::new();
+
+    for len in options.min_len..=options.max_len.min(MAX_PHASE_1_LEN) {
+        for &axis in &axes {
+            if reductions.len() >= options.max_results {
+                return reductions;
+            }
+
+            let cube = cube.conjugate(axis.to_ud());
+            if tables.domino_distance(cube) as usize > len {
+                continue;
+            }
+            // the node stack needs at least one move.
+            if len == 0 {
+                reductions.push(DominoReduction {
+                    axis,
+                    moves: Vec::new(),
+                });
+                continue;
+            }
+
+            dispatch_phase_1_len(
+                len,
+                CollectReductions {
+                    cube,
+                    axis,
+                    tables,
+                    table_offsets: &table_offsets,
+                    options,
+                    reductions: &mut reductions,
+                },
+            );
+        }
+    }
+
+    reductions
+}
+
+struct CollectReductions<'a> {
+    // already conjugated so that `axis` is the UD axis.
+    cube: ReprCube,
+    axis: Axis,
+    tables: &'a Tables,
+    table_offsets: &'a TableOffsets<'a>,
+    options: &'a DominoReductionOptions,
+    reductions: &'a mut Vec<DominoReduction>,
+}
+
+impl FixedPhase1Len for CollectReductions<'_> {
+    type Output = ();
+
+    fn run<const N: usize, const CAP: usize>(self) {
+        let Self {
+            cube,
+            axis,
+            tables,
+            table_offsets,
+            options,
+            reductions,
+        } = self;
+        let counters = SearchCounters::default();
+        let breaks_eo = |mv: &CubeMove| {
+            options
+                .preserve_eo
+                .is_some_and(|eo_axis| eo_axis.quarter_turns().contains(mv))
+        };
+
+        for (head, tail_a, tail_b) in
+            ud_domino_reductions::<N, CAP>(cube, tables, table_offsets, &counters)
+        {
+            // the two tails are the two quarter turns of the same last face.
+            let tail_a = tail_a.into_cube(tables);
+            let tail_b = tail_b.into_cube(tables);
+            let tails = if tail_a == tail_b {
+                vec![tail_a]
+            } else {
+                vec![tail_a, tail_b]
+            };
+
+            for tail in tails {
+                let cubes = head
+                    .iter()
+                    .map(|node| node.into_cube(tables))
+                    .chain(iter::once(tail))
+                    .map(|cube| cube.conjugate(axis.to_ud_inverse()));
+                let moves = move_resolver(cubes);
+
+                if moves.iter().any(breaks_eo) {
+                    continue;
+                }
+
+                reductions.push(DominoReduction { axis, moves });
+                if reductions.len() >= options.max_results {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cube;
+
+    use super::*;
+
+    fn reduces(cube: ReprCube, tables: &Tables, reduction: &DominoReduction) -> bool {
+        let reduced = reduction
+            .moves
+            .iter()
+            .fold(cube, |cube, &mv| cube.apply_cube_move(mv));
+        tables.is_domino_reduced(reduced.conjugate(reduction.axis.to_ud()))
+    }
+
+    #[test]
+    fn reductions_on_every_axis() -> anyhow::Result<()> {
+        let tables = Tables::new("tables")?;
+        let cube = cube![R U Rp Up F2 D L2 B Rp D2];
+
+        let reductions = domino_reductions(cube, &tables, &DominoReductionOptions::default());
+        assert!(!reductions.is_empty());
+        for axis in Axis::ALL {
+            let shortest = reductions
+                .iter()
+                .filter(|r| r.axis == axis)
+                .map(|r| r.moves.len())
+                .min();
+            assert_eq!(
+                shortest,
+                Some(tables.domino_distance(cube.conjugate(axis.to_ud())) as usize)
+            );
+        }
+        assert!(
+            reductions
+                .windows(2)
+                .all(|w| w[0].moves.len() <= w[1].moves.len())
+        );
+        for reduction in &reductions {
+            assert!(reduces(cube, &tables, reduction), "{reduction:?}");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn reductions_respect_options() -> anyhow::Result<()> {
+        let tables = Tables::new("tables")?;
+        // no F or B quarter turns, so the edges are still oriented on the FB axis.
+        let cube = cube![R U Rp Up F2 D L2 Rp D2 U];
+
+        let options = DominoReductionOptions {
+            axis: Some(Axis::RL),
+            min_len: 5,
+            max_len: 9,
+            preserve_eo: Some(Axis::FB),
+            max_results: 20,
+        };
+        let reductions = domino_reductions(cube, &tables, &options);
+        assert!(!reductions.is_empty() && reductions.len() <= 20);
+        for reduction in &reductions {
+            assert_eq!(reduction.axis, Axis::RL);
+            assert!((5..=9).contains(&reduction.moves.len()));
+            assert!(
+                reduction
+                    .moves
+                    .iter()
+                    .all(|mv| !Axis::FB.quarter_turns().contains(mv))
+            );
+            assert!(reduces(cube, &tables, reduction));
+        }
+
+        // an already reduced cube has exactly one empty reduction on its axis.
+        let reduced = cube![U R2 Dp F2];
+        let options = DominoReductionOptions {
+            axis: Some(Axis::UD),
+            max_len: 0,
+            ..Default::default()
+        };
+        assert_eq!(
+            domino_reductions(reduced, &tables, &options),
+            vec![DominoReduction {
+                axis: Axis::UD,
+                moves: vec![]
+            }]
+        );
+
+        Ok(())
+    }
+}
@@ -15,8 +15,10 @@ use crate::{
     kociemba::{
         coords::{CornerOrientRawCoord, EdgeGroupOrientSymCoord},
         search::{
+            depth_driver::{FixedPhase1Len, dispatch_phase_1_len},
             phase_1_node::{Phase1Node, TableOffsets},
             phase_2_node::Phase2Node,
+            search_counters::{LocalCounters, SearchCounters},
         },
         tables::Tables,
     },
@@ -28,12 +30,24 @@ pub fn all_domino_reductions<'a, const N: usize, const CAP: usize>(
     cube: ReprCube,
     tables: &'a Tables,
     table_offsets: &'a TableOffsets<'a>,
+    counters: &'a SearchCounters,
 ) -> impl Iterator<Item = ([Phase1Node; N], Phase2Node, Phase2Node)> {
-    Stack::<_, CAP, _>::new(cube, tables, table_offsets, ())
+    Stack::<_, CAP, _>::new(cube, tables, table_offsets, counters, ())
         .into_iter()
         .flatten()
 }
 
+/// like `all_domino_reductions`, but only the reductions to the UD axis domino group.
+pub fn ud_domino_reductions<'a, const N: usize, const CAP: usize>(
+    cube: ReprCube,
+    tables: &'a Tables,
+    table_offsets: &'a TableOffsets<'a>,
+    counters: &'a SearchCounters,
+) -> impl Iterator<Item = ([Phase1Node; N], Phase2Node, Phase2Node)> {
+    let start = Phase1Node::from_cube(cube, tables);
+    Stack::<N, CAP, _>::new_inner(start, tables, table_offsets, counters, ())
+}
+
 pub fn any_domino_reductions_const<const N: usize, const CAP: usize>(
     edge_group_orient_sym: EdgeGroupOrientSymCoord,
     corner_orient_raw: CornerOrientRawCoord,
@@ -41,7 +55,8 @@ pub fn any_domino_reductions_const<const N: usize, const CAP: usize>(
     table_offsets: &TableOffsets,
 ) -> u8 {
     let cube = Phase1Node::from_phase_1_coords(edge_group_orient_sym, corner_orient_raw, tables);
-    Stack::<N, CAP, _>::new_inner(cube, tables, table_offsets, ())
+    let counters = SearchCounters::default();
+    Stack::<N, CAP, _>::new_inner(cube, tables, table_offsets, &counters, ())
         .take(255)
         .count() as u8
 }
@@ -53,135 +68,36 @@ pub fn any_domino_reductions(
     table_offsets: &TableOffsets,
     n: u8,
 ) -> u8 {
-    match n {
-        0 => any_domino_reductions_const::<0, 4>(
-            edge_group_orient_sym,
-            corner_orient_raw,
-            tables,
-            table_offsets,
-        ),
-        1 => any_domino_reductions_const::<1, { 15 + 4 }>(
-            edge_group_orient_sym,
-            corner_orient_raw,
-            tables,
-            table_offsets,
-        ),
-        2 => any_domino_reductions_const::<2, { 2 * 15 + 4 }>(
-            edge_group_orient_sym,
-            corner_orient_raw,
-            tables,
-            table_offsets,
-        ),
-        3 => any_domino_reductions_const::<3, { 3 * 15 + 4 }>(
-            edge_group_orient_sym,
-            corner_orient_raw,
-            tables,
-            table_offsets,
-        ),
-        4 => any_domino_reductions_const::<4, { 4 * 15 + 4 }>(
-            edge_group_orient_sym,
-            corner_orient_raw,
-            tables,
-            table_offsets,
-        ),
-        5 => any_domino_reductions_const::<5, { 5 * 15 + 4 }>(
-            edge_group_orient_sym,
-            corner_orient_raw,
-            tables,
-            table_offsets,
-        ),
-        6 => any_domino_reductions_const::<6, { 6 * 15 + 4 }>(
-            edge_group_orient_sym,
-            corner_orient_raw,
-            tables,
-            table_offsets,
-        ),
-        7 => any_domino_reductions_const::<7, { 7 * 15 + 4 }>(
-            edge_group_orient_sym,
-            corner_orient_raw,
-            tables,
-            table_offsets,
-        ),
-        8 => any_domino_reductions_const::<8, { 8 * 15 + 4 }>(
-            edge_group_orient_sym,
-            corner_orient_raw,
-            tables,
-            table_offsets,
-        ),
-        9 => any_domino_reductions_const::<9, { 9 * 15 + 4 }>(
-            edge_group_orient_sym,
-            corner_orient_raw,
-            tables,
-            table_offsets,
-        ),
-        10 => any_domino_reductions_const::<10, { 10 * 15 + 4 }>(
-            edge_group_orient_sym,
-            corner_orient_raw,
-            tables,
-            table_offsets,
-        ),
-        11 => any_domino_reductions_const::<11, { 11 * 15 + 4 }>(
-            edge_group_orient_sym,
-            corner_orient_raw,
-            tables,
-            table_offsets,
-        ),
-        12 => any_domino_reductions_const::<12, { 12 * 15 + 4 }>(
-            edge_group_orient_sym,
-            corner_orient_raw,
-            tables,
-            table_offsets,
-        ),
-        13 => any_domino_reductions_const::<13, { 13 * 15 + 4 }>(
-            edge_group_orient_sym,
-            corner_orient_raw,
-            tables,
-            table_offsets,
-        ),
-        14 => any_domino_reductions_const::<14, { 14 * 15 + 4 }>(
-            edge_group_orient_sym,
-            corner_orient_raw,
-            tables,
-            table_offsets,
-        ),
-        15 => any_domino_reductions_const::<15, { 15 * 15 + 4 }>(
-            edge_group_orient_sym,
-            corner_orient_raw,
-            tables,
-            table_offsets,
-        ),
-        16 => any_domino_reductions_const::<16, { 16 * 15 + 4 }>(
-            edge_group_orient_sym,
-            corner_orient_raw,
-            tables,
-            table_offsets,
-        ),
-        17 => any_domino_reductions_const::<17, { 17 * 15 + 4 }>(
-            edge_group_orient_sym,
-            corner_orient_raw,
-            tables,
-            table_offsets,
-        ),
-        18 => any_domino_reductions_const::<18, { 18 * 15 + 4 }>(
-            edge_group_orient_sym,
-            corner_orient_raw,
-            tables,
-            table_offsets,
-        ),
-        19 => any_domino_reductions_const::<19, { 19 * 15 + 4 }>(
-            edge_group_orient_sym,
-            corner_orient_raw,
-            tables,
-            table_offsets,
-        ),
-        20 => any_domino_reductions_const::<20, { 20 * 15 + 4 }>(
+    struct AnyDominoReductions<'a> {
+        edge_group_orient_sym: EdgeGroupOrientSymCoord,
+        corner_orient_raw: CornerOrientRawCoord,
+        tables: &'a Tables,
+        table_offsets: &'a TableOffsets<'a>,
+    }
+
+    impl FixedPhase1Len for AnyDominoReductions<'_> {
+        type Output = u8;
+
+        fn run<const N: usize, const CAP: usize>(self) -> u8 {
+            any_domino_reductions_const::<N, CAP>(
+                self.edge_group_orient_sym,
+                self.corner_orient_raw,
+                self.tables,
+                self.table_offsets,
+            )
+        }
+    }
+
+    dispatch_phase_1_len(
+        n as usize,
+        AnyDominoReductions {
             edge_group_orient_sym,
             corner_orient_raw,
             tables,
             table_offsets,
-        ),
-        _ => unreachable!(),
-    }
+        },
+    )
+    .unwrap()
 }
 
 /// returns all sequences of sym cubes which correspond with a
@@ -191,19 +107,22 @@ pub fn all_domino_reductions_par<'a, const N: usize, const CAP: usize>(
     tables: &'a Tables,
     table_offsets: &'a TableOffsets<'a>,
     cancel: &'a AtomicBool,
+    counters: &'a SearchCounters,
 ) -> impl 'a + ParallelIterator<Item = ([Phase1Node; N], Phase2Node, Phase2Node)> {
-    Stack::<'a, N, CAP, &'a AtomicBool>::new(cube, tables, table_offsets, cancel)
+    Stack::<'a, N, CAP, &'a AtomicBool>::new(cube, tables, table_offsets, counters, cancel)
         .into_par_iter()
         .flatten()
 }
 
 // N is number of moves
 // this doesn't work if the cube is already domino reduced.
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 struct Stack<'t, const N: usize, const CAP: usize, C> {
     tables: &'t Tables,
     table_offsets: &'t TableOffsets<'t>,
 
+    counters: LocalCounters<'t>,
+
     cancel: C,
 
     frame_metadata: [FrameMetadata; N],
@@ -226,6 +145,7 @@ impl<'t, const N: usize, const CAP: usize, C: Clone> Stack<'t, N, CAP, C> {
         cube: ReprCube,
         tables: &'t Tables,
         table_offsets: &'t TableOffsets,
+        counters: &'t SearchCounters,
         cancel: C,
     ) -> Vec<Self> {
         debug_assert_eq!(15 * N + 4, CAP);
@@ -234,7 +154,7 @@ impl<'t, const N: usize, const CAP: usize, C: Clone> Stack<'t, N, CAP, C> {
         options.sort_by_key(|n| n.distance_heuristic(tables));
         options
             .into_iter()
-            .map(|node| Self::new_inner(node, tables, table_offsets, cancel.clone()))
+            .map(|node| Self::new_inner(node, tables, table_offsets, counters, cancel.clone()))
             .collect_vec()
     }
 
@@ -242,6 +162,7 @@ impl<'t, const N: usize, const CAP: usize, C: Clone> Stack<'t, N, CAP, C> {
         start: Phase1Node,
         tables: &'t Tables,
         table_offsets: &'t TableOffsets,
+        counters: &'t SearchCounters,
         cancel: C,
     ) -> Self {
         let starts = Some(start);
@@ -257,6 +178,7 @@ impl<'t, const N: usize, const CAP: usize, C: Clone> Stack<'t, N, CAP, C> {
         let mut stack = Self {
             tables,
             table_offsets,
+            counters: LocalCounters::new(counters),
             cancel,
             frame_data,
             frame_metadata: [FrameMetadata { start: 0 }; _],
@@ -298,6 +220,7 @@ impl<'t, const N: usize, const CAP: usize, C: Clone> Stack<'t, N, CAP, C> {
                     break;
                 }
 
+                self.counters.pruned += 1;
                 len -= 1;
             }
         }
@@ -331,6 +254,7 @@ impl<'t, const N: usize, const CAP: usize, C: Clone> Stack<'t, N, CAP, C> {
                         len += 1;
                     }
 
+                    self.counters.nodes_visited += (len - self.frame_data.len()) as u64;
                     self.frame_data.set_len(len);
                 }
             }
@@ -363,6 +287,7 @@ impl<'t, const N: usize, const CAP: usize, C: Clone> Stack<'t, N, CAP, C> {
             unsafe {
                 self.frame_data.set_len(self.frame_data.len() + added);
             }
+            self.counters.nodes_visited += added as u64;
 
             i += 1;
 
@@ -395,6 +320,9 @@ impl<'t, const N: usize, const CAP: usize, C: Clone> Iterator for Stack<'t, N, C
             self.fill_recurse_simd(unsafe { NonZeroUsize::new_unchecked(i) });
         };
 
+        self.counters.domino_reductions += 1;
+        self.counters.maybe_flush();
+
         Some((head, phase_2_head_a, phase_2_head_b))
     }
 }
@@ -451,6 +379,7 @@ impl<'t, const N: usize, const CAP: usize> UnindexedProducer for Stack<'t, N, CA
             let mut new_stack = Stack {
                 tables: self.tables,
                 table_offsets: self.table_offsets,
+                counters: LocalCounters::new(self.counters.shared()),
                 cancel: self.cancel,
                 frame_metadata: self.frame_metadata,
                 frame_data: new_frame_data,
@@ -506,9 +435,11 @@ mod test {
     fn domino_reduce_empty() -> anyhow::Result<()> {
         let tables = Tables::new("tables")?;
         let table_offsets = TableOffsets::new(&tables);
+        let counters = SearchCounters::default();
         let cube = cube![R U Rp Up];
         let stack =
-            all_domino_reductions::<0, { 0 * 15 + 4 }>(cube, &tables, &table_offsets).collect_vec();
+            all_domino_reductions::<0, { 0 * 15 + 4 }>(cube, &tables, &table_offsets, &counters)
+                .collect_vec();
 
         println!("{stack:#?}");
 
@@ -520,6 +451,7 @@ mod test {
         let tables = Tables::new("tables")?;
         let table_ref = &tables;
         let table_offsets = TableOffsets::new(&tables);
+        let counters = SearchCounters::default();
         let cube = cube![R U Rp Up];
         // let cube = cube![D R2 L];
         let res = move |path: &[Phase1Node], last: &Phase2Node| {
@@ -530,19 +462,23 @@ mod test {
 
             move_resolver_multi_dimension_domino(cube, cubes)
         };
-        let stack = all_domino_reductions::<2, { 2 * 15 + 4 }>(cube, &tables, &table_offsets);
+        let stack =
+            all_domino_reductions::<2, { 2 * 15 + 4 }>(cube, &tables, &table_offsets, &counters);
         stack.for_each(|(path, last_a, last_b)| {
             println!("{:?} {:?}", res(&path, &last_a), res(&path, &last_b));
         });
-        let stack = all_domino_reductions::<3, { 3 * 15 + 4 }>(cube, &tables, &table_offsets);
+        let stack =
+            all_domino_reductions::<3, { 3 * 15 + 4 }>(cube, &tables, &table_offsets, &counters);
         stack.for_each(|(path, last_a, last_b)| {
             println!("{:?} {:?}", res(&path, &last_a), res(&path, &last_b));
         });
-        let stack = all_domino_reductions::<4, { 4 * 15 + 4 }>(cube, &tables, &table_offsets);
+        let stack =
+            all_domino_reductions::<4, { 4 * 15 + 4 }>(cube, &tables, &table_offsets, &counters);
         stack.for_each(|(path, last_a, last_b)| {
             println!("{:?} {:?}", res(&path, &last_a), res(&path, &last_b));
         });
-        let stack = all_domino_reductions::<5, { 5 * 15 + 4 }>(cube, &tables, &table_offsets);
+        let stack =
+            all_domino_reductions::<5, { 5 * 15 + 4 }>(cube, &tables, &table_offsets, &counters);
         stack.for_each(|(path, last_a, last_b)| {
             println!("{:?} {:?}", res(&path, &last_a), res(&path, &last_b));
         });
@@ -554,11 +490,13 @@ mod test {
     fn domino_reduce_test_superflip_2_single() -> anyhow::Result<()> {
         let tables = Tables::new("tables")?;
         let table_offsets = TableOffsets::new(&tables);
+        let counters = SearchCounters::default();
 
         let stack = all_domino_reductions::<11, { 11 * 15 + 4 }>(
             cube![U R2 F B R B2 R U2 L B2 R Up Dp R2 F Rp L B2 U2 F2],
             &tables,
             &table_offsets,
+            &counters,
         );
 
         println!("{:?}", stack.count());
@@ -570,6 +508,7 @@ mod test {
     fn domino_reduce_test_superflip_2_par() -> anyhow::Result<()> {
         let tables = Tables::new("tables")?;
         let table_offsets = TableOffsets::new(&tables);
+        let counters = SearchCounters::default();
 
         let cancel = AtomicBool::new(false);
 
@@ -578,6 +517,7 @@ mod test {
             &tables,
             &table_offsets,
             &cancel,
+            &counters,
         );
 
         println!("{:?}", stack.count());
@@ -589,6 +529,7 @@ mod test {
     fn domino_reduction_length_chart() -> anyhow::Result<()> {
         let tables = Tables::new("tables")?;
         let table_offsets = TableOffsets::new(&tables);
+        let counters = SearchCounters::default();
 
         let mut rng = ChaCha8Rng::seed_from_u64(2);
         let cube: ReprCube =
@@ -597,53 +538,113 @@ mod test {
 
         println!(
             "0: {}",
-            all_domino_reductions_par::<0, { 0 * 15 + 4 }>(cube, &tables, &table_offsets, &cancel)
-                .count()
+            all_domino_reductions_par::<0, { 0 * 15 + 4 }>(
+                cube,
+                &tables,
+                &table_offsets,
+                &cancel,
+                &counters
+            )
+            .count()
         );
         println!(
             "1: {}",
-            all_domino_reductions_par::<1, { 1 * 15 + 4 }>(cube, &tables, &table_offsets, &cancel)
-                .count()
+            all_domino_reductions_par::<1, { 1 * 15 + 4 }>(
+                cube,
+                &tables,
+                &table_offsets,
+                &cancel,
+                &counters
+            )
+            .count()
         );
         println!(
             "2: {}",
-            all_domino_reductions_par::<2, { 2 * 15 + 4 }>(cube, &tables, &table_offsets, &cancel)
-                .count()
+            all_domino_reductions_par::<2, { 2 * 15 + 4 }>(
+                cube,
+                &tables,
+                &table_offsets,
+                &cancel,
+                &counters
+            )
+            .count()
         );
         println!(
             "3: {}",
-            all_domino_reductions_par::<3, { 3 * 15 + 4 }>(cube, &tables, &table_offsets, &cancel)
-                .count()
+            all_domino_reductions_par::<3, { 3 * 15 + 4 }>(
+                cube,
+                &tables,
+                &table_offsets,
+                &cancel,
+                &counters
+            )
+            .count()
         );
         println!(
             "4: {}",
-            all_domino_reductions_par::<4, { 4 * 15 + 4 }>(cube, &tables, &table_offsets, &cancel)
-                .count()
+            all_domino_reductions_par::<4, { 4 * 15 + 4 }>(
+                cube,
+                &tables,
+                &table_offsets,
+                &cancel,
+                &counters
+            )
+            .count()
         );
         println!(
             "5: {}",
-            all_domino_reductions_par::<5, { 5 * 15 + 4 }>(cube, &tables, &table_offsets, &cancel)
-                .count()
+            all_domino_reductions_par::<5, { 5 * 15 + 4 }>(
+                cube,
+                &tables,
+                &table_offsets,
+                &cancel,
+                &counters
+            )
+            .count()
         );
         println!(
             "6: {}",
-            all_domino_reductions_par::<6, { 6 * 15 + 4 }>(cube, &tables, &table_offsets, &cancel)
-                .count()
+            all_domino_reductions_par::<6, { 6 * 15 + 4 }>(
+                cube,
+                &tables,
+                &table_offsets,
+                &cancel,
+                &counters
+            )
+            .count()
         );
         println!(
             "7: {}",
-            all_domino_reductions_par::<7, { 7 * 15 + 4 }>(cube, &tables, &table_offsets, &cancel)
-                .count()
+            all_domino_reductions_par::<7, { 7 * 15 + 4 }>(
+                cube,
+                &tables,
+                &table_offsets,
+                &cancel,
+                &counters
+            )
+            .count()
         );
         println!(
             "8: {}",
-            all_domino_reductions_par::<8, { 8 * 15 + 4 }>(cube, &tables, &table_offsets, &cancel)
-                .count()
+            all_domino_reductions_par::<8, { 8 * 15 + 4 }>(
+                cube,
+                &tables,
+                &table_offsets,
+                &cancel,
+                &counters
+            )
+            .count()
         );
         println!(
             "9: {}",
-            all_domino_reductions_par::<9, { 9 * 15 + 4 }>(cube, &tables, &table_offsets, &cancel)
-                .count()
+            all_domino_reductions_par::<9, { 9 * 15 + 4 }>(
+                cube,
+                &tables,
+                &table_offsets,
+                &cancel,
+                &counters
+            )
+            .count()
         );
         println!(
             "10: {}",
@@ -651,7 +652,8 @@ mod test {
                 cube,
                 &tables,
                 &table_offsets,
-                &cancel
+                &cancel,
+                &counters
             )
             .count()
         );
@@ -661,7 +663,8 @@ mod test {
                 cube,
                 &tables,
                 &table_offsets,
-                &cancel
+                &cancel,
+                &counters
             )
             .count()
         );
@@ -671,7 +674,8 @@ mod test {
                 cube,
                 &tables,
                 &table_offsets,
-                &cancel
+                &cancel,
+                &counters
             )
             .count()
         );
@@ -1,10 +1,25 @@
-mod capped_idastar;
+pub mod capped_idastar;
+mod depth_driver;
+mod distance;
+mod domino_reduction;
 mod domino_reduction_iter;
 mod move_resolver;
 mod phase_1_node;
 mod phase_2_node;
+mod search_counters;
+mod solution;
+mod solve;
 mod solve_domino;
+mod solve_error;
 mod solve_with_fixed_len_phase_1;
 mod stream_search;
 
-pub use stream_search::get_incremental_solutions_stream;
+pub use domino_reduction::{DominoReduction, DominoReductionOptions, domino_reductions};
+pub use search_counters::{SearchEvent, ThreadThroughput};
+pub use solution::Solution;
+pub use solve::{SolutionIter, SolveOptions, solve, solve_iter};
+pub use solve_error::SolveError;
+pub use stream_search::{
+    SearchStream, SearchUpdate, SolutionStream, SolutionUpdate, get_incremental_solutions_stream,
+    get_incremental_solutions_stream_in, get_search_updates_stream,
+};
@@ -0,0 +1,323 @@
+use std::{
+    collections::BTreeMap,
+    sync::{
+        Mutex,
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+/// how many nodes a search worker counts locally before adding them to the shared counters.
+pub const LOCAL_FLUSH_THRESHOLD: u64 = 1 << 16;
+
+/// Counters shared by every worker of one search.
+///
+/// Workers keep their own counts in a `LocalCounters` and add them in batches, so the totals can
+/// lag behind the search by up to `LOCAL_FLUSH_THRESHOLD` nodes per worker.
+///
+/// Only the node count is always kept, since every `Solution` reports it. The rest are only added
+/// up with telemetry enabled.
+#[derive(Default)]
+pub struct SearchCounters {
+    telemetry: Option<Telemetry>,
+    phase_1_depth: AtomicUsize,
+    nodes_visited: AtomicU64,
+    domino_reductions: AtomicU64,
+    phase_2_solves: AtomicU64,
+    pruned: AtomicU64,
+    // nodes visited by each thread, keyed by rayon thread index. `None` is a thread outside the pool.
+    thread_nodes_visited: Mutex<BTreeMap<Option<usize>, u64>>,
+}
+
+// reports are sent by whichever worker flushes first once the interval has passed, so a search
+// doesn't need a thread of its own for them.
+struct Telemetry {
+    start: Instant,
+    interval: Duration,
+    report: Box<dyn Fn(SearchEvent) + Send + Sync>,
+    // when the next report is due, and the previous report.
+    state: Mutex<(Duration, Option<SearchEvent>)>,
+}
+
+impl std::fmt::Debug for SearchCounters {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SearchCounters")
+            .field("telemetry", &self.telemetry.is_some())
+            .field("phase_1_depth", &self.phase_1_depth)
+            .field("nodes_visited", &self.nodes_visited)
+            .field("domino_reductions", &self.domino_reductions)
+            .field("phase_2_solves", &self.phase_2_solves)
+            .field("pruned", &self.pruned)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SearchCounters {
+    /// counters that send a `SearchEvent` to `report` about every `interval`.
+    pub fn with_telemetry(
+        interval: Duration,
+        report: impl Fn(SearchEvent) + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            telemetry: Some(Telemetry {
+                start: Instant::now(),
+                interval,
+                report: Box::new(report),
+                state: Mutex::new((interval, None)),
+            }),
+            ..Default::default()
+        }
+    }
+
+    pub fn set_phase_1_depth(&self, depth: usize) {
+        self.phase_1_depth.store(depth, Ordering::Relaxed);
+    }
+
+    /// phase 1 and phase 2 nodes generated so far.
+    pub fn nodes_visited(&self) -> u64 {
+        self.nodes_visited.load(Ordering::Relaxed)
+    }
+
+    /// send a `SearchEvent` now, whether or not one is due.
+    pub fn report(&self) {
+        if let Some(telemetry) = &self.telemetry {
+            let mut state = telemetry.state.lock().unwrap();
+            self.report_locked(telemetry, &mut state);
+        }
+    }
+
+    fn maybe_report(&self) {
+        let Some(telemetry) = &self.telemetry else {
+            return;
+        };
+        // another worker is already reporting.
+        let Ok(mut state) = telemetry.state.try_lock() else {
+            return;
+        };
+        if telemetry.start.elapsed() >= state.0 {
+            self.report_locked(telemetry, &mut state);
+        }
+    }
+
+    fn report_locked(&self, telemetry: &Telemetry, state: &mut (Duration, Option<SearchEvent>)) {
+        let elapsed = telemetry.start.elapsed();
+        let event = self.snapshot(elapsed, state.1.as_ref());
+        (telemetry.report)(event.clone());
+        *state = (elapsed.saturating_add(telemetry.interval), Some(event));
+    }
+
+    /// read every counter. `previous` is the last snapshot of the same search, used for the
+    /// per thread throughput.
+    pub fn snapshot(&self, elapsed: Duration, previous: Option<&SearchEvent>) -> SearchEvent {
+        let thread_nodes_visited = self.thread_nodes_visited.lock().unwrap().clone();
+
+        let thread_throughput = thread_nodes_visited
+            .iter()
+            .map(|(&thread, &nodes_visited)| {
+                let (previous_nodes, previous_elapsed) = previous
+                    .and_then(|previous| {
+                        previous
+                            .thread_throughput
+                            .iter()
+                            .find(|t| t.thread == thread)
+                            .map(|t| (t.nodes_visited, previous.elapsed))
+                    })
+                    .unwrap_or((0, Duration::ZERO));
+                let seconds = elapsed.saturating_sub(previous_elapsed).as_secs_f64();
+
+                ThreadThroughput {
+                    thread,
+                    nodes_visited,
+                    nodes_per_second: if seconds > 0.0 {
+                        (nodes_visited - previous_nodes) as f64 / seconds
+                    } else {
+                        0.0
+                    },
+                }
+            })
+            .collect();
+
+        SearchEvent {
+            elapsed,
+            phase_1_depth: self.phase_1_depth.load(Ordering::Relaxed),
+            nodes_visited: self.nodes_visited(),
+            domino_reductions: self.domino_reductions.load(Ordering::Relaxed),
+            phase_2_solves: self.phase_2_solves.load(Ordering::Relaxed),
+            pruned: self.pruned.load(Ordering::Relaxed),
+            thread_throughput,
+        }
+    }
+}
+
+/// The counts of one worker that have not been added to the shared `SearchCounters` yet. They are
+/// added when the local node count passes `LOCAL_FLUSH_THRESHOLD`, and on drop.
+#[derive(Debug)]
+pub struct LocalCounters<'a> {
+    shared: &'a SearchCounters,
+    pub nodes_visited: u64,
+    pub domino_reductions: u64,
+    pub phase_2_solves: u64,
+    pub pruned: u64,
+}
+
+impl<'a> LocalCounters<'a> {
+    pub fn new(shared: &'a SearchCounters) -> Self {
+        Self {
+            shared,
+            nodes_visited: 0,
+            domino_reductions: 0,
+            phase_2_solves: 0,
+            pruned: 0,
+        }
+    }
+
+    pub fn shared(&self) -> &'a SearchCounters {
+        self.shared
+    }
+
+    #[inline(always)]
+    pub fn maybe_flush(&mut self) {
+        if self.nodes_visited >= LOCAL_FLUSH_THRESHOLD {
+            self.flush();
+        }
+    }
+
+    #[cold]
+    pub fn flush(&mut self) {
+        let shared = self.shared;
+        let nodes_visited = std::mem::take(&mut self.nodes_visited);
+        let domino_reductions = std::mem::take(&mut self.domino_reductions);
+        let phase_2_solves = std::mem::take(&mut self.phase_2_solves);
+        let pruned = std::mem::take(&mut self.pruned);
+
+        if nodes_visited != 0 {
+            shared
+                .nodes_visited
+                .fetch_add(nodes_visited, Ordering::Relaxed);
+        }
+
+        if shared.telemetry.is_none() {
+            return;
+        }
+
+        shared
+            .domino_reductions
+            .fetch_add(domino_reductions, Ordering::Relaxed);
+        shared
+            .phase_2_solves
+            .fetch_add(phase_2_solves, Ordering::Relaxed);
+        shared.pruned.fetch_add(pruned, Ordering::Relaxed);
+        if nodes_visited != 0 {
+            *shared
+                .thread_nodes_visited
+                .lock()
+                .unwrap()
+                .entry(rayon::current_thread_index())
+                .or_default() += nodes_visited;
+        }
+
+        shared.maybe_report();
+    }
+}
+
+impl Drop for LocalCounters<'_> {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+/// A periodic report on the progress of a search.
+#[derive(Clone, PartialEq, Debug)]
+pub struct SearchEvent {
+    /// time since the start of the search.
+    pub elapsed: Duration,
+    /// the phase 1 length currently being searched.
+    pub phase_1_depth: usize,
+    /// phase 1 and phase 2 nodes generated so far.
+    pub nodes_visited: u64,
+    /// phase 1 solutions found so far. each is a pair of phase 2 starts.
+    pub domino_reductions: u64,
+    /// phase 2 searches run on those domino reductions.
+    pub phase_2_solves: u64,
+    /// phase 1 nodes and domino reductions cut off by the pruning tables or the current best.
+    pub pruned: u64,
+    pub thread_throughput: Vec<ThreadThroughput>,
+}
+
+/// How many nodes one worker thread has visited.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ThreadThroughput {
+    /// the rayon thread index, or `None` for a thread outside the pool.
+    pub thread: Option<usize>,
+    pub nodes_visited: u64,
+    /// nodes visited per second since the previous event.
+    pub nodes_per_second: f64,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn reports_when_due() {
+        let events = std::sync::Arc::new(Mutex::new(Vec::new()));
+        let events_clone = events.clone();
+        let shared = SearchCounters::with_telemetry(Duration::ZERO, move |event| {
+            events_clone.lock().unwrap().push(event)
+        });
+
+        let mut local = LocalCounters::new(&shared);
+        local.nodes_visited += 3;
+        local.flush();
+        local.nodes_visited += 4;
+        local.flush();
+        shared.report();
+
+        let nodes: Vec<_> = events
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|event| event.nodes_visited)
+            .collect();
+        assert_eq!(nodes, vec![3, 7, 7]);
+    }
+
+    #[test]
+    fn flush_respects_telemetry() {
+        for telemetry in [false, true] {
+            let shared = if telemetry {
+                SearchCounters::with_telemetry(Duration::MAX, |_| {})
+            } else {
+                SearchCounters::default()
+            };
+
+            let mut local = LocalCounters::new(&shared);
+            local.nodes_visited += 10;
+            local.domino_reductions += 2;
+            local.phase_2_solves += 1;
+            local.pruned += 7;
+            local.maybe_flush();
+            assert_eq!(shared.nodes_visited(), 0);
+            drop(local);
+
+            let event = shared.snapshot(Duration::from_secs(2), None);
+            assert_eq!(event.nodes_visited, 10);
+            if telemetry {
+                assert_eq!(event.domino_reductions, 2);
+                assert_eq!(event.phase_2_solves, 1);
+                assert_eq!(event.pruned, 7);
+                assert_eq!(
+                    event.thread_throughput,
+                    vec![ThreadThroughput {
+                        thread: None,
+                        nodes_visited: 10,
+                        nodes_per_second: 5.0,
+                    }]
+                );
+            } else {
+                assert_eq!(event.domino_reductions, 0);
+                assert!(event.thread_throughput.is_empty());
+            }
+        }
+    }
+}
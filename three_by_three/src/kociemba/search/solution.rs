@@ -0,0 +1,94 @@
+use std::time::Duration;
+
+use crate::cube_ops::cube_move::CubeMove;
+
+/// A solution found by the two phase search.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Solution {
+    /// the moves that solve the cube, in order.
+    pub moves: Vec<CubeMove>,
+    /// how many of `moves` reach domino reduction. the rest solve the reduced cube.
+    pub phase_1_len: usize,
+    /// time from the start of the search until this solution was found.
+    pub elapsed: Duration,
+    /// phase 1 and phase 2 nodes generated before this solution was found.
+    pub nodes_visited: u64,
+    /// whether every shorter solution was ruled out when this one was reported. only set for a
+    /// solved cube and by `solve` with `SolveOptions::optimal`. the streams and `solve_iter` report
+    /// the proof for their last solution as a separate `ProvenOptimal` update instead.
+    pub proven_optimal: bool,
+}
+
+impl Solution {
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    pub fn phase_1(&self) -> &[CubeMove] {
+        &self.moves[..self.phase_1_len]
+    }
+
+    pub fn phase_2(&self) -> &[CubeMove] {
+        &self.moves[self.phase_1_len..]
+    }
+}
+
+impl AsRef<[CubeMove]> for Solution {
+    fn as_ref(&self) -> &[CubeMove] {
+        &self.moves
+    }
+}
+
+/// standard notation with the phase boundary marked by a `.`, followed by the length, e.g.
+/// `R U' F2 . D2 R2 (5)`. optimal solutions have their length marked with a `*`.
+impl std::fmt::Display for Solution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for m in self.phase_1() {
+            write!(f, "{m} ")?;
+        }
+        write!(f, ".")?;
+        for m in self.phase_2() {
+            write!(f, " {m}")?;
+        }
+        write!(f, " ({}", self.len())?;
+        if self.proven_optimal {
+            write!(f, "*")?;
+        }
+        write!(f, ")")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn display_marks_phase_boundary() {
+        let solution = Solution {
+            moves: vec![
+                CubeMove::R1,
+                CubeMove::U3,
+                CubeMove::F2,
+                CubeMove::D2,
+                CubeMove::R2,
+            ],
+            phase_1_len: 3,
+            elapsed: Duration::ZERO,
+            nodes_visited: 0,
+            proven_optimal: false,
+        };
+        assert_eq!(solution.to_string(), "R U' F2 . D2 R2 (5)");
+
+        let solved = Solution {
+            moves: vec![],
+            phase_1_len: 0,
+            proven_optimal: true,
+            ..solution
+        };
+        assert_eq!(solved.to_string(), ". (0*)");
+    }
+}
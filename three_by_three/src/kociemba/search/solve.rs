@@ -0,0 +1,216 @@
+use std::{
+    panic::AssertUnwindSafe,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, AtomicU8, Ordering},
+    },
+};
+
+use crate::{
+    cube_ops::repr_cube::ReprCube,
+    kociemba::{
+        search::{
+            depth_driver::{DepthDriver, MAX_PHASE_1_LEN},
+            search_counters::SearchCounters,
+            solution::Solution,
+            solve_error::{SolveError, no_solution},
+            stream_search::{SolutionStream, SolutionUpdate, spawn_search},
+        },
+        tables::Tables,
+    },
+};
+
+/// Options for `solve` and `solve_iter`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SolveOptions {
+    /// only solutions of at most this many moves are reported.
+    pub max_moves: Option<u8>,
+    /// split the search over the rayon pool.
+    pub parallel: bool,
+    /// keep searching until the best solution is proven optimal, instead of stopping at the first
+    /// one found.
+    pub optimal: bool,
+}
+
+impl Default for SolveOptions {
+    fn default() -> Self {
+        Self {
+            max_moves: None,
+            parallel: true,
+            optimal: false,
+        }
+    }
+}
+
+/// solve `cube` on the calling thread, blocking until the search is done.
+///
+/// returns the first solution within `options.max_moves`, or the optimal one if `options.optimal`
+/// is set.
+pub fn solve(
+    cube: ReprCube,
+    tables: &Tables,
+    options: SolveOptions,
+) -> Result<Solution, SolveError> {
+    cube.validate()?;
+
+    let cancel = AtomicBool::new(false);
+    let best = AtomicU8::new(options.max_moves.unwrap_or(u8::MAX).saturating_add(1));
+    let counters = SearchCounters::default();
+    let best_solution = Mutex::new(None::<Solution>);
+
+    let driver = DepthDriver {
+        cube,
+        tables,
+        cancel: &cancel,
+        best: &best,
+        counters: &counters,
+        max_phase_1_len: MAX_PHASE_1_LEN,
+    };
+    let run = || {
+        driver.run(options.parallel, |solution| {
+            let mut best_solution = best_solution.lock().unwrap();
+            if best_solution
+                .as_ref()
+                .is_none_or(|best| solution.len() < best.len())
+            {
+                *best_solution = Some(solution);
+            }
+            if !options.optimal {
+                cancel.store(true, Ordering::Release);
+            }
+        })
+    };
+    let proven = std::panic::catch_unwind(AssertUnwindSafe(run)).map_err(SolveError::from_panic)?;
+
+    let mut solution = best_solution
+        .into_inner()
+        .unwrap()
+        .ok_or_else(|| no_solution(options.max_moves))?;
+    solution.proven_optimal |= proven;
+    Ok(solution)
+}
+
+/// the solutions of `cube` as they are found, or the error that ended the search. the search runs
+/// on the global rayon pool, and is cancelled when the iterator is dropped.
+///
+/// like `solve`, it stops at the first solution within `options.max_moves`. with
+/// `options.optimal` it yields every improving solution, shortest last, then
+/// `SolutionUpdate::ProvenOptimal` once the search has run to completion.
+pub fn solve_iter(cube: ReprCube, tables: Arc<Tables>, options: SolveOptions) -> SolutionIter {
+    let stream = spawn_search(
+        None,
+        cube,
+        tables,
+        options.max_moves,
+        options.parallel,
+        None,
+    );
+    SolutionIter {
+        stream: Some(futures::executor::block_on_stream(SolutionStream(stream))),
+        optimal: options.optimal,
+    }
+}
+
+/// The blocking iterator returned by `solve_iter`.
+pub struct SolutionIter {
+    // `None` once the iterator is done. dropping the stream cancels the search.
+    stream: Option<futures::executor::BlockingStream<SolutionStream>>,
+    optimal: bool,
+}
+
+impl Iterator for SolutionIter {
+    type Item = Result<SolutionUpdate, SolveError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let update = self.stream.as_mut()?.next();
+        if !self.optimal && matches!(update, Some(Ok(SolutionUpdate::Solution(_)))) {
+            self.stream = None;
+        }
+        update
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{cube, cube_ops::repr_cube::InvalidCube};
+
+    use super::*;
+
+    #[test]
+    fn solve_first_and_optimal() -> anyhow::Result<()> {
+        let tables = Tables::new("tables")?;
+        let cube = cube![R U Rp Up F2 D L2 B Rp D2];
+
+        let first = solve(cube, &tables, SolveOptions::default())?;
+        let solved = first
+            .moves
+            .iter()
+            .fold(cube, |cube, &cube_move| cube.apply_cube_move(cube_move));
+        assert_eq!(solved, ReprCube::SOLVED);
+
+        let optimal = solve(
+            cube,
+            &tables,
+            SolveOptions {
+                optimal: true,
+                ..Default::default()
+            },
+        )?;
+        assert!(optimal.proven_optimal);
+        assert_eq!(optimal.len(), 10);
+
+        let too_short = solve(
+            cube,
+            &tables,
+            SolveOptions {
+                max_moves: Some(9),
+                optimal: true,
+                ..Default::default()
+            },
+        );
+        assert!(matches!(
+            too_short,
+            Err(SolveError::LimitReached { max_moves: 9 })
+        ));
+
+        let mut twisted = cube;
+        twisted.corner_orient.0[0] = (twisted.corner_orient.0[0] + 1) % 3;
+        assert!(matches!(
+            solve(twisted, &tables, SolveOptions::default()),
+            Err(SolveError::InvalidCube(InvalidCube::CornerOrient))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn solve_iter_improves() -> anyhow::Result<()> {
+        let tables = Arc::new(Tables::new("tables")?);
+        let cube = cube![R U Rp Up F2 D L2 B Rp D2];
+
+        // only the first solution, as `solve` returns.
+        let first: Vec<_> =
+            solve_iter(cube, tables.clone(), SolveOptions::default()).collect::<Result<_, _>>()?;
+        assert!(matches!(first[..], [SolutionUpdate::Solution(_)]));
+
+        let options = SolveOptions {
+            optimal: true,
+            ..Default::default()
+        };
+        let updates: Vec<_> = solve_iter(cube, tables, options).collect::<Result<_, _>>()?;
+        let Some((SolutionUpdate::ProvenOptimal, solutions)) = updates.split_last() else {
+            panic!("expected the iterator to end with the proof");
+        };
+        let lengths: Vec<_> = solutions
+            .iter()
+            .map(|update| match update {
+                SolutionUpdate::Solution(solution) => solution.len(),
+                SolutionUpdate::ProvenOptimal => panic!("expected the proof only once"),
+            })
+            .collect();
+        assert!(lengths.windows(2).all(|w| w[0] > w[1]));
+        assert_eq!(lengths.last(), Some(&10));
+
+        Ok(())
+    }
+}
@@ -2,7 +2,9 @@ use arrayvec::ArrayVec;
 
 use crate::{
     Tables,
-    kociemba::search::{capped_idastar::idastar_limited, phase_2_node::Phase2Node},
+    kociemba::search::{
+        capped_idastar::idastar_limited, phase_2_node::Phase2Node, search_counters::LocalCounters,
+    },
 };
 
 /// solve a phase 2 cube.
@@ -35,21 +37,33 @@ pub fn solve_domino_pair(
     tables: &Tables,
     weak_max_moves: u8,
     strong_max_moves: impl FnOnce() -> Option<u8>,
+    counters: &mut LocalCounters,
 ) -> Option<ArrayVec<Phase2Node, 20>> {
     let phase_2_a_weak_dist = phase_2_start_a.weak_distance_heuristic(tables);
 
     if phase_2_a_weak_dist + 1 > weak_max_moves {
+        counters.pruned += 1;
         return None;
     }
-    let max_moves = strong_max_moves()?;
+    let Some(max_moves) = strong_max_moves() else {
+        counters.pruned += 1;
+        return None;
+    };
 
     if phase_2_a_weak_dist + 1 > max_moves {
+        counters.pruned += 1;
         return None;
     }
 
+    counters.phase_2_solves += 1;
+    let mut nodes_visited = 0;
+
     let solution_a = idastar_limited(
         phase_2_start_a,
-        |&cube| cube.produce_next_nodes(tables).map(|c| (c, 1)),
+        |&cube| {
+            nodes_visited += 1;
+            cube.produce_next_nodes(tables).map(|c| (c, 1))
+        },
         |&cube| cube.distance_heuristic(tables),
         |&cube| cube.is_solved(),
         max_moves,
@@ -58,13 +72,19 @@ pub fn solve_domino_pair(
 
     let solution_b = idastar_limited(
         phase_2_start_b,
-        |&cube| cube.produce_next_nodes(tables).map(|c| (c, 1)),
+        |&cube| {
+            nodes_visited += 1;
+            cube.produce_next_nodes(tables).map(|c| (c, 1))
+        },
         |&cube| cube.distance_heuristic(tables),
         |&cube| cube.is_solved(),
         max_moves,
     )
     .map(|(solution, _len)| solution);
 
+    counters.nodes_visited += nodes_visited;
+    counters.maybe_flush();
+
     match (solution_a, solution_b) {
         (None, None) => None,
         (None, Some(sol)) => Some(sol),
@@ -0,0 +1,68 @@
+use crate::cube_ops::repr_cube::InvalidCube;
+
+/// Why a search ended without a solution.
+#[derive(Debug)]
+pub enum SolveError {
+    /// the cube can't be solved.
+    InvalidCube(InvalidCube),
+    /// the tables could not be loaded, or gave a result that sound tables can't.
+    Tables(anyhow::Error),
+    /// a search worker panicked. holds the panic message.
+    WorkerPanicked(String),
+    /// the search finished without a solution within the move limit.
+    LimitReached { max_moves: u8 },
+}
+
+impl SolveError {
+    /// the message of a panic caught by `catch_unwind`.
+    pub(crate) fn from_panic(payload: Box<dyn std::any::Any + Send>) -> Self {
+        let message = match payload.downcast::<String>() {
+            Ok(message) => *message,
+            Err(payload) => match payload.downcast::<&'static str>() {
+                Ok(message) => message.to_string(),
+                Err(_) => "unknown panic payload".to_string(),
+            },
+        };
+        SolveError::WorkerPanicked(message)
+    }
+}
+
+impl std::fmt::Display for SolveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SolveError::InvalidCube(invalid) => write!(f, "invalid cube: {invalid}"),
+            SolveError::Tables(err) => write!(f, "bad tables: {err}"),
+            SolveError::WorkerPanicked(message) => write!(f, "search worker panicked: {message}"),
+            SolveError::LimitReached { max_moves } => {
+                write!(f, "no solution within {max_moves} moves")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SolveError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SolveError::InvalidCube(invalid) => Some(invalid),
+            SolveError::Tables(err) => Some(err.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<InvalidCube> for SolveError {
+    fn from(invalid: InvalidCube) -> Self {
+        SolveError::InvalidCube(invalid)
+    }
+}
+
+/// the error for a search that ran to completion without finding a solution.
+pub(crate) fn no_solution(max_moves: Option<u8>) -> SolveError {
+    match max_moves {
+        Some(max_moves) => SolveError::LimitReached { max_moves },
+        // every valid cube has a solution with a phase 1 of at most 12 moves.
+        None => SolveError::Tables(anyhow::anyhow!(
+            "no solution with any phase 1 length, the pruning tables are likely corrupt"
+        )),
+    }
+}
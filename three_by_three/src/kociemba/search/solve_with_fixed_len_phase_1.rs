@@ -6,7 +6,9 @@ use crate::{
     Tables,
     cube_ops::{cube_move::CubeMove, repr_cube::ReprCube},
     kociemba::search::{
-        move_resolver::move_resolver_multi_dimension_domino, phase_1_node::TableOffsets,
+        move_resolver::move_resolver_multi_dimension_domino,
+        phase_1_node::TableOffsets,
+        search_counters::{LocalCounters, SearchCounters},
         solve_domino::solve_domino_pair,
     },
 };
@@ -17,14 +19,19 @@ pub fn produce_solutions<'t, const N: usize, const CAP: usize>(
     current_best: u8,
     tables: &'t Tables,
     table_offsets: &'t TableOffsets,
+    counters: &'t SearchCounters,
 ) -> impl 't + Iterator<Item = Vec<CubeMove>> {
-    let domino_reductions =
-        super::domino_reduction_iter::all_domino_reductions::<N, CAP>(cube, tables, table_offsets);
+    let domino_reductions = super::domino_reduction_iter::all_domino_reductions::<N, CAP>(
+        cube,
+        tables,
+        table_offsets,
+        counters,
+    );
 
     domino_reductions
         .scan(
-            current_best,
-            |current_best, (phase_1, phase_2_start_a, phase_2_start_b)| {
+            (current_best, LocalCounters::new(counters)),
+            |(current_best, local_counters), (phase_1, phase_2_start_a, phase_2_start_b)| {
                 let phase_2_max = *current_best - N as u8;
 
                 let Some(phase_2) = solve_domino_pair(
@@ -33,6 +40,7 @@ pub fn produce_solutions<'t, const N: usize, const CAP: usize>(
                     tables,
                     phase_2_max,
                     || Some(*current_best - N as u8),
+                    local_counters,
                 ) else {
                     return Some(None);
                 };
@@ -49,11 +57,6 @@ pub fn produce_solutions<'t, const N: usize, const CAP: usize>(
         })
 }
 
-// Thread-local storage for the last seen value
-thread_local! {
-    static THREAD_LOCAL_BEST: std::cell::Cell<u8> = const { std::cell::Cell::new(u8::MAX) }
-}
-
 /// produce all solutions with phase 1 solutions of length N in parallel
 pub fn produce_solutions_par<'a, const N: usize, const CAP: usize>(
     cube: ReprCube,
@@ -61,40 +64,48 @@ pub fn produce_solutions_par<'a, const N: usize, const CAP: usize>(
     tables: &'a Tables,
     table_offsets: &'a TableOffsets,
     cancel: &'a AtomicBool,
+    counters: &'a SearchCounters,
 ) -> impl 'a + ParallelIterator<Item = Vec<CubeMove>> {
     let domino_reductions = super::domino_reduction_iter::all_domino_reductions_par::<N, CAP>(
         cube,
         tables,
         table_offsets,
         cancel,
+        counters,
     );
 
     domino_reductions
-        .filter_map(|(phase_1, phase_2_start_a, phase_2_start_b)| {
-            let local_best = THREAD_LOCAL_BEST.with(|tl| tl.get());
-            let local_phase_2_max = local_best.checked_sub(N as u8)?;
-
-            let phase_2 = solve_domino_pair(
-                phase_2_start_a,
-                phase_2_start_b,
-                tables,
-                local_phase_2_max,
-                || {
-                    let current_best = best.load(std::sync::atomic::Ordering::Relaxed);
-                    THREAD_LOCAL_BEST.replace(current_best);
-                    current_best.checked_sub(N as u8)
-                },
-            )?;
-            let new_path_len = (N + phase_2.len() - 1) as u8;
-
-            // println!("")
-            let old = best.fetch_min(new_path_len, std::sync::atomic::Ordering::AcqRel);
-            if new_path_len >= old {
-                return None;
-            }
-
-            Some((phase_1, phase_2))
-        })
+        .map_init(
+            || LocalCounters::new(counters),
+            |local_counters, (phase_1, phase_2_start_a, phase_2_start_b)| {
+                // rayon workers outlive a search, so the bound has to come from this search's `best`.
+                let local_phase_2_max = best
+                    .load(std::sync::atomic::Ordering::Relaxed)
+                    .checked_sub(N as u8)?;
+
+                let phase_2 = solve_domino_pair(
+                    phase_2_start_a,
+                    phase_2_start_b,
+                    tables,
+                    local_phase_2_max,
+                    || {
+                        best.load(std::sync::atomic::Ordering::Relaxed)
+                            .checked_sub(N as u8)
+                    },
+                    local_counters,
+                )?;
+                let new_path_len = (N + phase_2.len() - 1) as u8;
+
+                // println!("")
+                let old = best.fetch_min(new_path_len, std::sync::atomic::Ordering::AcqRel);
+                if new_path_len >= old {
+                    return None;
+                }
+
+                Some((phase_1, phase_2))
+            },
+        )
+        .flatten()
         .map(move |(phase_1, phase_2)| {
             let phase_1 = phase_1.into_iter().map(|node| node.into_cube(tables));
             let phase_2 = phase_2.into_iter().map(|node| node.into_cube(tables));
@@ -114,12 +125,14 @@ mod test {
     fn solve_combined_test_superflip_magic_s() -> anyhow::Result<()> {
         let tables = Tables::new("tables")?;
         let table_offsets = TableOffsets::new(&tables);
+        let counters = SearchCounters::default();
 
         let solutions = produce_solutions::<10, { 10 * 15 + 4 }>(
             cube![U R2 F B R B2 R U2 L B2 R Up Dp R2 F Rp L B2 U2 F2],
             u8::MAX,
             &tables,
             &table_offsets,
+            &counters,
         );
 
         for solution in solutions {
@@ -137,6 +150,7 @@ mod test {
     fn solve_combined_test_superflip_magic_par() -> anyhow::Result<()> {
         let tables = Tables::new("tables")?;
         let table_offsets = TableOffsets::new(&tables);
+        let counters = SearchCounters::default();
 
         let best = AtomicU8::new(u8::MAX);
         let cancel = AtomicBool::new(false);
@@ -147,6 +161,7 @@ mod test {
             &tables,
             &table_offsets,
             &cancel,
+            &counters,
         );
 
         let block = Mutex::new(());
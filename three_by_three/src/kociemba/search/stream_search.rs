@@ -1,610 +1,283 @@
 use std::{
+    ops::Deref,
     panic::AssertUnwindSafe,
     pin::Pin,
     sync::{
         Arc,
-        atomic::{AtomicBool, AtomicU8},
+        atomic::{AtomicBool, AtomicU8, Ordering},
     },
-    thread::JoinHandle,
+    task::{Context, Poll},
+    time::Duration,
 };
 
 use flume::{Sender, r#async::RecvStream};
-use futures::{StreamExt, future};
+use futures::StreamExt;
 use futures_core::Stream;
-use rayon::iter::ParallelIterator;
+use rayon::ThreadPool;
 
 use crate::{
     cube_ops::{cube_move::CubeMove, repr_cube::ReprCube},
     kociemba::{
         search::{
+            depth_driver::{DepthDriver, FirstSolution, MAX_PHASE_1_LEN, dispatch_phase_1_len},
             phase_1_node::TableOffsets,
-            solve_with_fixed_len_phase_1::{produce_solutions, produce_solutions_par},
+            search_counters::{SearchCounters, SearchEvent},
+            solution::Solution,
+            solve_error::{SolveError, no_solution},
         },
         tables::Tables,
     },
 };
 
-fn solver_thread_single(
-    cube: ReprCube,
-    tables: &Tables,
-    send: Sender<Vec<CubeMove>>,
-    cancel: Arc<AtomicBool>,
-    seed_best: u8,
-) {
-    let mut best = AtomicU8::new(seed_best);
-
-    let table_offsets = TableOffsets::new(tables);
-
-    if cube == ReprCube::SOLVED {
-        let _ = send.send(Vec::new());
-        return;
-    }
-
-    if *(best.get_mut()) <= 1 {
-        return;
-    }
-    if cancel.load(std::sync::atomic::Ordering::Acquire) {
-        return;
-    }
-    produce_solutions::<1, { 15 + 4 }>(cube, *best.get_mut(), tables, &table_offsets).for_each(
-        |solution| {
-            *best.get_mut() = (solution.len() - 1) as u8;
-            let _ = send.send(solution);
-        },
-    );
-
-    if *(best.get_mut()) <= 2 + 1 {
-        return;
-    }
-    if cancel.load(std::sync::atomic::Ordering::Acquire) {
-        return;
-    }
-    produce_solutions::<2, { 2 * 15 + 4 }>(cube, *best.get_mut(), tables, &table_offsets).for_each(
-        |solution| {
-            *best.get_mut() = (solution.len() - 1) as u8;
-            let _ = send.send(solution);
-        },
-    );
-
-    if *(best.get_mut()) <= 3 + 1 {
-        return;
-    }
-    if cancel.load(std::sync::atomic::Ordering::Acquire) {
-        return;
-    }
-    produce_solutions::<3, { 3 * 15 + 4 }>(cube, *best.get_mut(), tables, &table_offsets).for_each(
-        |solution| {
-            *best.get_mut() = (solution.len() - 1) as u8;
-            let _ = send.send(solution);
-        },
-    );
-
-    if *(best.get_mut()) <= 4 + 1 {
-        return;
-    }
-    if cancel.load(std::sync::atomic::Ordering::Acquire) {
-        return;
-    }
-    produce_solutions::<4, { 4 * 15 + 4 }>(cube, *best.get_mut(), tables, &table_offsets).for_each(
-        |solution| {
-            *best.get_mut() = (solution.len() - 1) as u8;
-            let _ = send.send(solution);
-        },
-    );
-
-    if *(best.get_mut()) <= 5 + 1 {
-        return;
-    }
-    if cancel.load(std::sync::atomic::Ordering::Acquire) {
-        return;
-    }
-    produce_solutions::<5, { 5 * 15 + 4 }>(cube, *best.get_mut(), tables, &table_offsets).for_each(
-        |solution| {
-            *best.get_mut() = (solution.len() - 1) as u8;
-            let _ = send.send(solution);
-        },
-    );
-
-    if *(best.get_mut()) <= 6 + 1 {
-        return;
-    }
-    if cancel.load(std::sync::atomic::Ordering::Acquire) {
-        return;
-    }
-    produce_solutions::<6, { 6 * 15 + 4 }>(cube, *best.get_mut(), tables, &table_offsets).for_each(
-        |solution| {
-            *best.get_mut() = (solution.len() - 1) as u8;
-            let _ = send.send(solution);
-        },
-    );
-
-    if *(best.get_mut()) <= 7 + 1 {
-        return;
-    }
-    if cancel.load(std::sync::atomic::Ordering::Acquire) {
-        return;
-    }
-    produce_solutions::<7, { 7 * 15 + 4 }>(cube, *best.get_mut(), tables, &table_offsets).for_each(
-        |solution| {
-            *best.get_mut() = (solution.len() - 1) as u8;
-            let _ = send.send(solution);
-        },
-    );
-
-    if *(best.get_mut()) <= 8 + 1 {
-        return;
-    }
-    if cancel.load(std::sync::atomic::Ordering::Acquire) {
-        return;
-    }
-    produce_solutions::<8, { 8 * 15 + 4 }>(cube, *best.get_mut(), tables, &table_offsets).for_each(
-        |solution| {
-            *best.get_mut() = (solution.len() - 1) as u8;
-            let _ = send.send(solution);
-        },
-    );
-
-    if *(best.get_mut()) <= 9 + 1 {
-        return;
-    }
-    if cancel.load(std::sync::atomic::Ordering::Acquire) {
-        return;
-    }
-    produce_solutions::<9, { 9 * 15 + 4 }>(cube, *best.get_mut(), tables, &table_offsets).for_each(
-        |solution| {
-            *best.get_mut() = (solution.len() - 1) as u8;
-            let _ = send.send(solution);
-        },
-    );
-
-    if *(best.get_mut()) <= 10 + 1 {
-        return;
-    }
-    if cancel.load(std::sync::atomic::Ordering::Acquire) {
-        return;
-    }
-    produce_solutions::<10, { 10 * 15 + 4 }>(cube, *best.get_mut(), tables, &table_offsets)
-        .for_each(|solution| {
-            *best.get_mut() = (solution.len() - 1) as u8;
-            let _ = send.send(solution);
-        });
-
-    if *(best.get_mut()) <= 11 + 1 {
-        return;
-    }
-    if cancel.load(std::sync::atomic::Ordering::Acquire) {
-        return;
-    }
-    produce_solutions::<11, { 11 * 15 + 4 }>(cube, *best.get_mut(), tables, &table_offsets)
-        .for_each(|solution| {
-            *best.get_mut() = (solution.len() - 1) as u8;
-            let _ = send.send(solution);
-        });
-
-    if *(best.get_mut()) <= 12 + 1 {
-        return;
-    }
-    if cancel.load(std::sync::atomic::Ordering::Acquire) {
-        return;
-    }
-    produce_solutions::<12, { 12 * 15 + 4 }>(cube, *best.get_mut(), tables, &table_offsets)
-        .for_each(|solution| {
-            *best.get_mut() = (solution.len() - 1) as u8;
-            let _ = send.send(solution);
-        });
-
-    if *(best.get_mut()) <= 13 + 1 {
-        return;
-    }
-    if cancel.load(std::sync::atomic::Ordering::Acquire) {
-        return;
-    }
-    produce_solutions::<13, { 13 * 15 + 4 }>(cube, *best.get_mut(), tables, &table_offsets)
-        .for_each(|solution| {
-            *best.get_mut() = (solution.len() - 1) as u8;
-            let _ = send.send(solution);
-        });
-
-    if *(best.get_mut()) <= 14 + 1 {
-        return;
-    }
-    if cancel.load(std::sync::atomic::Ordering::Acquire) {
-        return;
-    }
-    produce_solutions::<14, { 14 * 15 + 4 }>(cube, *best.get_mut(), tables, &table_offsets)
-        .for_each(|solution| {
-            *best.get_mut() = (solution.len() - 1) as u8;
-            let _ = send.send(solution);
-        });
-
-    if *(best.get_mut()) <= 15 + 1 {
-        return;
-    }
-    if cancel.load(std::sync::atomic::Ordering::Acquire) {
-        return;
-    }
-    produce_solutions::<15, { 15 * 15 + 4 }>(cube, *best.get_mut(), tables, &table_offsets)
-        .for_each(|solution| {
-            *best.get_mut() = (solution.len() - 1) as u8;
-            let _ = send.send(solution);
-        });
-
-    if *(best.get_mut()) <= 16 + 1 {
-        return;
-    }
-    if cancel.load(std::sync::atomic::Ordering::Acquire) {
-        return;
-    }
-    produce_solutions::<16, { 16 * 15 + 4 }>(cube, *best.get_mut(), tables, &table_offsets)
-        .for_each(|solution| {
-            *best.get_mut() = (solution.len() - 1) as u8;
-            let _ = send.send(solution);
-        });
-
-    if *(best.get_mut()) <= 17 + 1 {
-        return;
-    }
-    if cancel.load(std::sync::atomic::Ordering::Acquire) {
-        return;
-    }
-    produce_solutions::<17, { 17 * 15 + 4 }>(cube, *best.get_mut(), tables, &table_offsets)
-        .for_each(|solution| {
-            *best.get_mut() = (solution.len() - 1) as u8;
-            let _ = send.send(solution);
-        });
-
-    if *(best.get_mut()) <= 18 + 1 {
-        return;
-    }
-    if cancel.load(std::sync::atomic::Ordering::Acquire) {
-        return;
-    }
-    produce_solutions::<18, { 18 * 15 + 4 }>(cube, *best.get_mut(), tables, &table_offsets)
-        .for_each(|solution| {
-            *best.get_mut() = (solution.len() - 1) as u8;
-            let _ = send.send(solution);
-        });
-
-    if *(best.get_mut()) <= 19 + 1 {
-        return;
-    }
-    if cancel.load(std::sync::atomic::Ordering::Acquire) {
-        return;
-    }
-    produce_solutions::<19, { 19 * 15 + 4 }>(cube, *best.get_mut(), tables, &table_offsets)
-        .for_each(|solution| {
-            *best.get_mut() = (solution.len() - 1) as u8;
-            let _ = send.send(solution);
-        });
+/// Something a running search reports.
+#[derive(Clone, PartialEq, Debug)]
+pub enum SearchUpdate {
+    /// a solution shorter than every one before it.
+    Solution(Solution),
+    /// the search ran to completion, so the last solution sent is optimal.
+    ProvenOptimal,
+    Event(SearchEvent),
+}
 
-    if *(best.get_mut()) <= 20 + 1 {
-        return;
-    }
-    if cancel.load(std::sync::atomic::Ordering::Acquire) {
-        return;
-    }
-    produce_solutions::<20, { 20 * 15 + 4 }>(cube, *best.get_mut(), tables, &table_offsets)
-        .for_each(|solution| {
-            *best.get_mut() = (solution.len() - 1) as u8;
-            let _ = send.send(solution);
-        });
+/// Something a `SolutionStream` reports, a `SearchUpdate` without the telemetry.
+#[derive(Clone, PartialEq, Debug)]
+pub enum SolutionUpdate {
+    /// a solution shorter than every one before it.
+    Solution(Solution),
+    /// the search ran to completion, so the last solution sent is optimal. nothing follows it.
+    ProvenOptimal,
 }
 
-fn solver_thread_parallel(
+type UpdateSender = Sender<Result<SearchUpdate, SolveError>>;
+
+fn run_search(
     cube: ReprCube,
     tables: &Tables,
-    send: Sender<Vec<CubeMove>>,
-    cancel: Arc<AtomicBool>,
-    seed_best: u8,
-) {
-    let mut best = AtomicU8::new(seed_best);
-
-    let table_offsets = TableOffsets::new(tables);
-
-    if cube == ReprCube::SOLVED {
-        let _ = send.send(Vec::new());
-        return;
-    }
-
-    if *(best.get_mut()) <= 1 {
-        return;
-    }
-    if cancel.load(std::sync::atomic::Ordering::Acquire) {
-        return;
-    }
-    produce_solutions_par::<1, { 15 + 4 }>(cube, &best, tables, &table_offsets, &cancel).for_each(
-        |solution| {
-            let _ = send.send(solution);
-        },
-    );
-
-    if *(best.get_mut()) <= 2 + 1 {
-        return;
-    }
-    if cancel.load(std::sync::atomic::Ordering::Acquire) {
-        return;
-    }
-    produce_solutions_par::<2, { 2 * 15 + 4 }>(cube, &best, tables, &table_offsets, &cancel)
-        .for_each(|solution| {
-            let _ = send.send(solution);
-        });
+    send: &UpdateSender,
+    cancel: &AtomicBool,
+    max_moves: Option<u8>,
+    parallel: bool,
+    telemetry_interval: Option<Duration>,
+) -> Result<(), SolveError> {
+    cube.validate()?;
+
+    let best = AtomicU8::new(max_moves.unwrap_or(u8::MAX).saturating_add(1));
+    let counters = match telemetry_interval {
+        Some(interval) => {
+            let send = send.clone();
+            SearchCounters::with_telemetry(interval, move |event| {
+                let _ = send.send(Ok(SearchUpdate::Event(event)));
+            })
+        }
+        None => SearchCounters::default(),
+    };
+    let found = AtomicBool::new(false);
 
-    if *(best.get_mut()) <= 3 + 1 {
-        return;
+    let proven = DepthDriver {
+        cube,
+        tables,
+        cancel,
+        best: &best,
+        counters: &counters,
+        max_phase_1_len: MAX_PHASE_1_LEN,
     }
-    if cancel.load(std::sync::atomic::Ordering::Acquire) {
-        return;
+    .run(parallel, |solution| {
+        found.store(true, Ordering::Relaxed);
+        let _ = send.send(Ok(SearchUpdate::Solution(solution)));
+    });
+    if proven {
+        let _ = send.send(Ok(SearchUpdate::ProvenOptimal));
     }
-    produce_solutions_par::<3, { 3 * 15 + 4 }>(cube, &best, tables, &table_offsets, &cancel)
-        .for_each(|solution| {
-            let _ = send.send(solution);
-        });
 
-    if *(best.get_mut()) <= 4 + 1 {
-        return;
-    }
-    if cancel.load(std::sync::atomic::Ordering::Acquire) {
-        return;
-    }
-    produce_solutions_par::<4, { 4 * 15 + 4 }>(cube, &best, tables, &table_offsets, &cancel)
-        .for_each(|solution| {
-            let _ = send.send(solution);
-        });
+    // the last event has the final counts.
+    counters.report();
 
-    if *(best.get_mut()) <= 5 + 1 {
-        return;
+    if !found.into_inner() && !cancel.load(Ordering::Acquire) {
+        return Err(no_solution(max_moves));
     }
-    if cancel.load(std::sync::atomic::Ordering::Acquire) {
-        return;
-    }
-    produce_solutions_par::<5, { 5 * 15 + 4 }>(cube, &best, tables, &table_offsets, &cancel)
-        .for_each(|solution| {
-            let _ = send.send(solution);
-        });
-
-    if *(best.get_mut()) <= 6 + 1 {
-        return;
-    }
-    if cancel.load(std::sync::atomic::Ordering::Acquire) {
-        return;
-    }
-    produce_solutions_par::<6, { 6 * 15 + 4 }>(cube, &best, tables, &table_offsets, &cancel)
-        .for_each(|solution| {
-            let _ = send.send(solution);
-        });
+    Ok(())
+}
 
-    if *(best.get_mut()) <= 7 + 1 {
-        return;
-    }
-    if cancel.load(std::sync::atomic::Ordering::Acquire) {
-        return;
-    }
-    produce_solutions_par::<7, { 7 * 15 + 4 }>(cube, &best, tables, &table_offsets, &cancel)
-        .for_each(|solution| {
-            let _ = send.send(solution);
-        });
+/// run the search as a job on `pool`, or on the global rayon pool. a parallel search also splits
+/// its work on that pool. the job keeps `tables` until it's done.
+pub(super) fn spawn_search(
+    pool: Option<&ThreadPool>,
+    cube: ReprCube,
+    tables: impl Deref<Target = Tables> + Send + 'static,
+    max_moves: Option<u8>,
+    parallel: bool,
+    telemetry_interval: Option<Duration>,
+) -> SearchStream {
+    let (send, recv) = flume::unbounded();
+    let cancel = Arc::new(AtomicBool::new(false));
+    let cancel_clone = cancel.clone();
+    let job = move || {
+        // a panic escaping a rayon job would abort the process, so it's sent as an error instead.
+        let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+            run_search(
+                cube,
+                &tables,
+                &send,
+                &cancel_clone,
+                max_moves,
+                parallel,
+                telemetry_interval,
+            )
+        }))
+        .unwrap_or_else(|payload| Err(SolveError::from_panic(payload)));
 
-    if *(best.get_mut()) <= 8 + 1 {
-        return;
-    }
-    if cancel.load(std::sync::atomic::Ordering::Acquire) {
-        return;
-    }
-    produce_solutions_par::<8, { 8 * 15 + 4 }>(cube, &best, tables, &table_offsets, &cancel)
-        .for_each(|solution| {
-            let _ = send.send(solution);
-        });
+        if let Err(err) = result {
+            let _ = send.send(Err(err));
+        }
+    };
 
-    if *(best.get_mut()) <= 9 + 1 {
-        return;
-    }
-    if cancel.load(std::sync::atomic::Ordering::Acquire) {
-        return;
+    match pool {
+        Some(pool) => pool.spawn(job),
+        None => rayon::spawn(job),
     }
-    produce_solutions_par::<9, { 9 * 15 + 4 }>(cube, &best, tables, &table_offsets, &cancel)
-        .for_each(|solution| {
-            let _ = send.send(solution);
-        });
 
-    if *(best.get_mut()) <= 10 + 1 {
-        return;
-    }
-    if cancel.load(std::sync::atomic::Ordering::Acquire) {
-        return;
+    SearchStream {
+        recv: recv.into_stream(),
+        cancel: CancelOnDrop(cancel),
+        best: usize::MAX,
     }
-    produce_solutions_par::<10, { 10 * 15 + 4 }>(cube, &best, tables, &table_offsets, &cancel)
-        .for_each(|solution| {
-            let _ = send.send(solution);
-        });
+}
 
-    if *(best.get_mut()) <= 11 + 1 {
-        return;
-    }
-    if cancel.load(std::sync::atomic::Ordering::Acquire) {
-        return;
-    }
-    produce_solutions_par::<11, { 11 * 15 + 4 }>(cube, &best, tables, &table_offsets, &cancel)
-        .for_each(|solution| {
-            let _ = send.send(solution);
-        });
+pub fn get_incremental_solutions_stream(
+    cube: ReprCube,
+    tables: &'static Tables,
+    max_moves: Option<u8>,
+    parallel: bool,
+) -> SolutionStream {
+    SolutionStream(spawn_search(None, cube, tables, max_moves, parallel, None))
+}
 
-    if *(best.get_mut()) <= 12 + 1 {
-        return;
-    }
-    if cancel.load(std::sync::atomic::Ordering::Acquire) {
-        return;
-    }
-    produce_solutions_par::<12, { 12 * 15 + 4 }>(cube, &best, tables, &table_offsets, &cancel)
-        .for_each(|solution| {
-            let _ = send.send(solution);
-        });
+/// like `get_incremental_solutions_stream`, but the search runs on `pool` instead of the global
+/// rayon pool.
+pub fn get_incremental_solutions_stream_in(
+    pool: &ThreadPool,
+    cube: ReprCube,
+    tables: &'static Tables,
+    max_moves: Option<u8>,
+    parallel: bool,
+) -> SolutionStream {
+    SolutionStream(spawn_search(
+        Some(pool),
+        cube,
+        tables,
+        max_moves,
+        parallel,
+        None,
+    ))
+}
 
-    if *(best.get_mut()) <= 13 + 1 {
-        return;
-    }
-    if cancel.load(std::sync::atomic::Ordering::Acquire) {
-        return;
-    }
-    produce_solutions_par::<13, { 13 * 15 + 4 }>(cube, &best, tables, &table_offsets, &cancel)
-        .for_each(|solution| {
-            let _ = send.send(solution);
-        });
+/// like `get_incremental_solutions_stream`, but also sends a `SearchEvent` about every
+/// `telemetry_interval`, and one more when the search is over.
+pub fn get_search_updates_stream(
+    cube: ReprCube,
+    tables: &'static Tables,
+    max_moves: Option<u8>,
+    parallel: bool,
+    telemetry_interval: Duration,
+) -> SearchStream {
+    spawn_search(
+        None,
+        cube,
+        tables,
+        max_moves,
+        parallel,
+        Some(telemetry_interval),
+    )
+}
 
-    if *(best.get_mut()) <= 14 + 1 {
-        return;
-    }
-    if cancel.load(std::sync::atomic::Ordering::Acquire) {
-        return;
-    }
-    produce_solutions_par::<14, { 14 * 15 + 4 }>(cube, &best, tables, &table_offsets, &cancel)
-        .for_each(|solution| {
-            let _ = send.send(solution);
-        });
+struct CancelOnDrop(Arc<AtomicBool>);
 
-    if *(best.get_mut()) <= 15 + 1 {
-        return;
-    }
-    if cancel.load(std::sync::atomic::Ordering::Acquire) {
-        return;
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Release);
     }
-    produce_solutions_par::<15, { 15 * 15 + 4 }>(cube, &best, tables, &table_offsets, &cancel)
-        .for_each(|solution| {
-            let _ = send.send(solution);
-        });
+}
 
-    if *(best.get_mut()) <= 16 + 1 {
-        return;
-    }
-    if cancel.load(std::sync::atomic::Ordering::Acquire) {
-        return;
-    }
-    produce_solutions_par::<16, { 16 * 15 + 4 }>(cube, &best, tables, &table_offsets, &cancel)
-        .for_each(|solution| {
-            let _ = send.send(solution);
-        });
+/// The updates of a search running on a rayon pool.
+///
+/// Dropping the stream cancels the search without waiting for it to stop, so it never blocks the
+/// task that owns it. Use `cancel` to wait until it has stopped.
+///
+/// A search that can't finish ends the stream with an error: an invalid cube, a worker panic, or no
+/// solution within the move limit.
+pub struct SearchStream {
+    recv: RecvStream<'static, Result<SearchUpdate, SolveError>>,
+    cancel: CancelOnDrop,
+    // the length of the best solution so far.
+    best: usize,
+}
 
-    if *(best.get_mut()) <= 17 + 1 {
-        return;
-    }
-    if cancel.load(std::sync::atomic::Ordering::Acquire) {
-        return;
-    }
-    produce_solutions_par::<17, { 17 * 15 + 4 }>(cube, &best, tables, &table_offsets, &cancel)
-        .for_each(|solution| {
-            let _ = send.send(solution);
-        });
+impl SearchStream {
+    /// cancel the search. the future resolves once the search has stopped.
+    pub fn cancel(self) -> impl Future<Output = ()> + Send + 'static {
+        let Self {
+            mut recv, cancel, ..
+        } = self;
+        drop(cancel);
 
-    if *(best.get_mut()) <= 18 + 1 {
-        return;
+        // the search holds the sender until it stops.
+        async move { while recv.next().await.is_some() {} }
     }
-    if cancel.load(std::sync::atomic::Ordering::Acquire) {
-        return;
-    }
-    produce_solutions_par::<18, { 18 * 15 + 4 }>(cube, &best, tables, &table_offsets, &cancel)
-        .for_each(|solution| {
-            let _ = send.send(solution);
-        });
+}
 
-    if *(best.get_mut()) <= 19 + 1 {
-        return;
-    }
-    if cancel.load(std::sync::atomic::Ordering::Acquire) {
-        return;
-    }
-    produce_solutions_par::<19, { 19 * 15 + 4 }>(cube, &best, tables, &table_offsets, &cancel)
-        .for_each(|solution| {
-            let _ = send.send(solution);
-        });
+impl Stream for SearchStream {
+    type Item = Result<SearchUpdate, SolveError>;
 
-    if *(best.get_mut()) <= 20 + 1 {
-        return;
-    }
-    if cancel.load(std::sync::atomic::Ordering::Acquire) {
-        return;
-    }
-    produce_solutions_par::<20, { 20 * 15 + 4 }>(cube, &best, tables, &table_offsets, &cancel)
-        .for_each(|solution| {
-            let _ = send.send(solution);
-        });
-}
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            let update = match Pin::new(&mut this.recv).poll_next(cx) {
+                Poll::Ready(Some(update)) => update,
+                other => return other,
+            };
 
-pub fn get_incremental_solutions_stream(
-    cube: ReprCube,
-    tables: &'static Tables,
-    max_moves: Option<u8>,
-    parallel: bool,
-) -> impl Stream<Item = Vec<CubeMove>> {
-    let (send, recv) = flume::unbounded();
-    let cancel = Arc::new(AtomicBool::new(false));
-    let cancel_clone = cancel.clone();
-    let join_handle = std::thread::spawn(move || {
-        let send = send;
-        let cancel = cancel_clone;
-        let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
-            if parallel {
-                solver_thread_parallel(
-                    cube,
-                    tables,
-                    send,
-                    cancel,
-                    max_moves.unwrap_or(u8::MAX).saturating_add(1),
-                );
-            } else {
-                solver_thread_single(
-                    cube,
-                    tables,
-                    send,
-                    cancel,
-                    max_moves.unwrap_or(u8::MAX).saturating_add(1),
-                );
+            if let Ok(SearchUpdate::Solution(solution)) = &update {
+                if solution.len() >= this.best {
+                    continue;
+                }
+                this.best = solution.len();
             }
-        }));
 
-        if let Err(err) = result {
-            eprintln!("[worker] panicked: {:?}", err);
-            std::panic::resume_unwind(err);
+            return Poll::Ready(Some(update));
         }
-    });
-
-    ImprovingSolutionStream {
-        recv: recv.into_stream(),
-        cancel,
-        join_handle: Some(join_handle),
     }
-    .scan(usize::MAX, |best_len, solution: Vec<CubeMove>| {
-        let emit = if solution.len() < *best_len {
-            *best_len = solution.len();
-            Some(solution)
-        } else {
-            None
-        };
-        future::ready(Some(emit))
-    })
-    .filter_map(future::ready)
 }
 
-struct ImprovingSolutionStream<'a> {
-    recv: RecvStream<'a, Vec<CubeMove>>,
-    cancel: Arc<AtomicBool>,
-    join_handle: Option<JoinHandle<()>>,
-}
+/// A `SearchStream` without the telemetry, yielding the improving solutions and the proof that the
+/// last one is optimal.
+pub struct SolutionStream(pub(super) SearchStream);
 
-impl<'a> Drop for ImprovingSolutionStream<'a> {
-    fn drop(&mut self) {
-        self.cancel
-            .store(true, std::sync::atomic::Ordering::Release);
-        self.join_handle.take().unwrap().join().unwrap();
+impl SolutionStream {
+    /// cancel the search. the future resolves once the search has stopped.
+    pub fn cancel(self) -> impl Future<Output = ()> + Send + 'static {
+        self.0.cancel()
     }
 }
 
-impl<'a> Stream for ImprovingSolutionStream<'a> {
-    type Item = Vec<CubeMove>;
+impl Stream for SolutionStream {
+    type Item = Result<SolutionUpdate, SolveError>;
 
-    fn poll_next(
-        self: std::pin::Pin<&mut Self>,
-        cx: &mut std::task::Context<'_>,
-    ) -> std::task::Poll<Option<Self::Item>> {
-        unsafe { Pin::new_unchecked(&mut self.get_unchecked_mut().recv) }.poll_next(cx)
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            match Pin::new(&mut this.0).poll_next(cx) {
+                Poll::Ready(Some(Ok(SearchUpdate::Solution(solution)))) => {
+                    return Poll::Ready(Some(Ok(SolutionUpdate::Solution(solution))));
+                }
+                Poll::Ready(Some(Ok(SearchUpdate::ProvenOptimal))) => {
+                    return Poll::Ready(Some(Ok(SolutionUpdate::ProvenOptimal)));
+                }
+                Poll::Ready(Some(Ok(SearchUpdate::Event(_)))) => continue,
+                Poll::Ready(Some(Err(err))) => return Poll::Ready(Some(Err(err))),
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
     }
 }
 
@@ -613,113 +286,16 @@ pub fn solve_direct(cube: ReprCube, tables: &Tables) -> Vec<CubeMove> {
     let phase_1 = super::phase_1_node::Phase1Node::from_cube(cube, tables);
     let domino_dist = phase_1.distance_heuristic(tables);
 
-    match domino_dist {
-        0 => super::solve_with_fixed_len_phase_1::produce_solutions::<0, 4>(
-            cube,
-            255,
-            tables,
-            &table_offsets,
-        )
-        .next()
-        .unwrap(),
-        1 => super::solve_with_fixed_len_phase_1::produce_solutions::<1, { 15 + 4 }>(
-            cube,
-            255,
-            tables,
-            &table_offsets,
-        )
-        .next()
-        .unwrap(),
-        2 => super::solve_with_fixed_len_phase_1::produce_solutions::<2, { 2 * 15 + 4 }>(
-            cube,
-            255,
-            tables,
-            &table_offsets,
-        )
-        .next()
-        .unwrap(),
-        3 => super::solve_with_fixed_len_phase_1::produce_solutions::<3, { 3 * 15 + 4 }>(
-            cube,
-            255,
-            tables,
-            &table_offsets,
-        )
-        .next()
-        .unwrap(),
-        4 => super::solve_with_fixed_len_phase_1::produce_solutions::<4, { 4 * 15 + 4 }>(
-            cube,
-            255,
-            tables,
-            &table_offsets,
-        )
-        .next()
-        .unwrap(),
-        5 => super::solve_with_fixed_len_phase_1::produce_solutions::<5, { 5 * 15 + 4 }>(
-            cube,
-            255,
-            tables,
-            &table_offsets,
-        )
-        .next()
-        .unwrap(),
-        6 => super::solve_with_fixed_len_phase_1::produce_solutions::<6, { 6 * 15 + 4 }>(
-            cube,
-            255,
-            tables,
-            &table_offsets,
-        )
-        .next()
-        .unwrap(),
-        7 => super::solve_with_fixed_len_phase_1::produce_solutions::<7, { 7 * 15 + 4 }>(
-            cube,
-            255,
-            tables,
-            &table_offsets,
-        )
-        .next()
-        .unwrap(),
-        8 => super::solve_with_fixed_len_phase_1::produce_solutions::<8, { 8 * 15 + 4 }>(
+    dispatch_phase_1_len(
+        domino_dist as usize,
+        FirstSolution {
             cube,
-            255,
             tables,
-            &table_offsets,
-        )
-        .next()
-        .unwrap(),
-        9 => super::solve_with_fixed_len_phase_1::produce_solutions::<9, { 9 * 15 + 4 }>(
-            cube,
-            255,
-            tables,
-            &table_offsets,
-        )
-        .next()
-        .unwrap(),
-        10 => super::solve_with_fixed_len_phase_1::produce_solutions::<10, { 10 * 15 + 4 }>(
-            cube,
-            255,
-            tables,
-            &table_offsets,
-        )
-        .next()
-        .unwrap(),
-        11 => super::solve_with_fixed_len_phase_1::produce_solutions::<11, { 11 * 15 + 4 }>(
-            cube,
-            255,
-            tables,
-            &table_offsets,
-        )
-        .next()
-        .unwrap(),
-        12 => super::solve_with_fixed_len_phase_1::produce_solutions::<12, { 12 * 15 + 4 }>(
-            cube,
-            255,
-            tables,
-            &table_offsets,
-        )
-        .next()
-        .unwrap(),
-        _ => unreachable!(),
-    }
+            table_offsets: &table_offsets,
+        },
+    )
+    .flatten()
+    .unwrap()
 }
 
 #[cfg(test)]
@@ -730,7 +306,15 @@ mod test {
     extern crate test;
 
     use super::*;
-    use crate::cube;
+    use crate::{cube, cube_ops::repr_cube::InvalidCube};
+
+    // the first update of `stream`, which is a solution unless the search failed.
+    fn first_solution(stream: &mut SolutionStream) -> Result<Solution, SolveError> {
+        match futures::executor::block_on(stream.next()).unwrap()? {
+            SolutionUpdate::Solution(solution) => Ok(solution),
+            SolutionUpdate::ProvenOptimal => panic!("expected a solution before the proof"),
+        }
+    }
 
     #[test]
     fn test_stream_superflip() -> anyhow::Result<()> {
@@ -743,11 +327,9 @@ mod test {
             true,
         );
 
-        let future = stream.next();
-
-        let solution = futures::executor::block_on(future).unwrap();
+        let solution = first_solution(&mut stream)?;
         print!("{:02} ", solution.len());
-        for m in solution {
+        for m in solution.moves {
             print!("{m} ");
         }
         println!("");
@@ -766,9 +348,13 @@ mod test {
             true,
         );
 
-        for solution in futures::executor::block_on_stream(stream) {
+        for update in futures::executor::block_on_stream(stream) {
+            let SolutionUpdate::Solution(solution) = update? else {
+                println!("optimal");
+                continue;
+            };
             print!("{:02} ", solution.len());
-            for m in solution {
+            for m in solution.moves {
                 print!("{m} ");
             }
             println!("");
@@ -791,9 +377,13 @@ mod test {
         cube.pretty_print();
         let stream = get_incremental_solutions_stream(cube, tables, None, true);
 
-        for solution in futures::executor::block_on_stream(stream) {
+        for update in futures::executor::block_on_stream(stream) {
+            let SolutionUpdate::Solution(solution) = update? else {
+                println!("optimal");
+                continue;
+            };
             print!("{:02} ", solution.len());
-            for m in solution.into_iter().rev() {
+            for m in solution.moves.into_iter().rev() {
                 let m = match m {
                     CubeMove::U1 => CubeMove::U3,
                     CubeMove::U2 => CubeMove::U2,
@@ -833,13 +423,10 @@ mod test {
             // cube.pretty_print();
 
             let mut stream = get_incremental_solutions_stream(cube, tables, Some(20), true);
-            let future = stream.next();
-            // assert!(futures::executor::block_on(future).is_some());
-
-            let solution = futures::executor::block_on(future).unwrap();
+            let solution = first_solution(&mut stream)?;
             {
                 print!("{:02} ", solution.len());
-                for m in solution.into_iter().rev() {
+                for m in solution.moves.into_iter().rev() {
                     let m = match m {
                         CubeMove::U1 => CubeMove::U3,
                         CubeMove::U2 => CubeMove::U2,
@@ -899,7 +486,7 @@ mod test {
             i += 1;
             let mut stream = get_incremental_solutions_stream(*cube, tables, Some(20), true);
             let future = stream.next();
-            let solution = futures::executor::block_on(future).unwrap();
+            let solution = futures::executor::block_on(future).unwrap().unwrap();
             test::black_box(solution);
         });
     }
@@ -920,7 +507,7 @@ mod test {
             i += 1;
             let mut stream = get_incremental_solutions_stream(*cube, tables, Some(20), false);
             let future = stream.next();
-            let solution = futures::executor::block_on(future).unwrap();
+            let solution = futures::executor::block_on(future).unwrap().unwrap();
             test::black_box(solution);
         });
     }
@@ -941,7 +528,7 @@ mod test {
             i += 1;
             let mut stream = get_incremental_solutions_stream(*cube, tables, Some(21), true);
             let future = stream.next();
-            let solution = futures::executor::block_on(future).unwrap();
+            let solution = futures::executor::block_on(future).unwrap().unwrap();
             test::black_box(solution);
         });
     }
@@ -962,7 +549,7 @@ mod test {
             i += 1;
             let mut stream = get_incremental_solutions_stream(*cube, tables, Some(22), true);
             let future = stream.next();
-            let solution = futures::executor::block_on(future).unwrap();
+            let solution = futures::executor::block_on(future).unwrap().unwrap();
             test::black_box(solution);
         });
     }
@@ -1031,7 +618,7 @@ mod test {
         bench.iter(|| {
             let mut stream = get_incremental_solutions_stream(cube, tables, Some(20), true);
             let future = stream.next();
-            let solution = futures::executor::block_on(future).unwrap();
+            let solution = futures::executor::block_on(future).unwrap().unwrap();
             test::black_box(solution);
         });
     }
@@ -1060,9 +647,157 @@ mod test {
         let tables = Box::leak(Box::new(Tables::new("tables")?));
 
         let mut stream = get_incremental_solutions_stream(ReprCube::SOLVED, tables, Some(20), true);
-        let future = stream.next();
-        let solution = futures::executor::block_on(future).unwrap();
+        let solution = first_solution(&mut stream)?;
         assert_eq!(solution.len(), 0);
+        assert!(solution.proven_optimal);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_updates_stream() -> anyhow::Result<()> {
+        let tables = Box::leak(Box::new(Tables::new("tables")?));
+        let cube = cube![R U Rp Up F2 D L2 B Rp D2];
+
+        let updates: Vec<_> = futures::executor::block_on_stream(get_search_updates_stream(
+            cube,
+            tables,
+            None,
+            true,
+            Duration::from_millis(10),
+        ))
+        .collect::<Result<_, _>>()?;
+
+        // the proof comes right after the optimal solution, and only once.
+        let proof = updates
+            .iter()
+            .position(|update| matches!(update, SearchUpdate::ProvenOptimal))
+            .unwrap();
+        let last_solution = updates[..proof]
+            .iter()
+            .rev()
+            .find_map(|update| match update {
+                SearchUpdate::Solution(solution) => Some(solution),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(last_solution.len(), 10);
+        assert!(
+            updates[proof + 1..]
+                .iter()
+                .all(|update| matches!(update, SearchUpdate::Event(_)))
+        );
+
+        // the last event is sent after the search is over, so it has every count.
+        let Some(SearchUpdate::Event(event)) = updates.last() else {
+            panic!("expected the stream to end with an event");
+        };
+        println!("{event:?}");
+        assert!(event.nodes_visited > 0);
+        assert!(event.domino_reductions > 0);
+        assert!(event.phase_2_solves > 0);
+        assert!(event.pruned > 0);
+        assert!(event.phase_1_depth > 0);
+        assert_eq!(
+            event
+                .thread_throughput
+                .iter()
+                .map(|t| t.nodes_visited)
+                .sum::<u64>(),
+            event.nodes_visited
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_cancel_on_pool() -> anyhow::Result<()> {
+        let tables = Box::leak(Box::new(Tables::new("tables")?));
+        let pool = rayon::ThreadPoolBuilder::new().num_threads(2).build()?;
+        let cube = cube![R U Rp Up F2 D L2 B Rp D2];
+
+        let mut stream = get_incremental_solutions_stream_in(&pool, cube, tables, None, true);
+        let solution = first_solution(&mut stream)?;
+        let solved = solution
+            .moves
+            .iter()
+            .fold(cube, |cube, &cube_move| cube.apply_cube_move(cube_move));
+        assert_eq!(solved, ReprCube::SOLVED);
+
+        // cancelling only flags the search, waiting for it is up to the caller.
+        let start = std::time::Instant::now();
+        let stopped = stream.cancel();
+        assert!(start.elapsed() < Duration::from_millis(50));
+        futures::executor::block_on(stopped);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_stream_errors() -> anyhow::Result<()> {
+        let tables = Box::leak(Box::new(Tables::new("tables")?));
+        let cube = cube![R U Rp Up F2 D L2 B Rp D2];
+
+        let mut flipped = cube;
+        flipped.edge_orient.0[0] ^= 1;
+        let updates: Vec<_> = futures::executor::block_on_stream(get_incremental_solutions_stream(
+            flipped, tables, None, true,
+        ))
+        .collect();
+        assert!(matches!(
+            updates[..],
+            [Err(SolveError::InvalidCube(InvalidCube::EdgeOrient))]
+        ));
+
+        let updates: Vec<_> = futures::executor::block_on_stream(get_incremental_solutions_stream(
+            cube,
+            tables,
+            Some(9),
+            true,
+        ))
+        .collect();
+        assert!(matches!(
+            updates[..],
+            [Err(SolveError::LimitReached { max_moves: 9 })]
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_stream_ends_with_proven_optimal() -> anyhow::Result<()> {
+        let tables = Box::leak(Box::new(Tables::new("tables")?));
+        let cube = cube![R U Rp Up F2 D L2 B Rp D2];
+
+        let updates: Vec<_> = futures::executor::block_on_stream(get_incremental_solutions_stream(
+            cube, tables, None, true,
+        ))
+        .collect::<Result<_, _>>()?;
+        let Some((SolutionUpdate::ProvenOptimal, solutions)) = updates.split_last() else {
+            panic!("expected the stream to end with the proof");
+        };
+
+        // every solution is sent once, shorter each time.
+        let solutions: Vec<_> = solutions
+            .iter()
+            .map(|update| match update {
+                SolutionUpdate::Solution(solution) => solution,
+                SolutionUpdate::ProvenOptimal => panic!("expected the proof only once"),
+            })
+            .collect();
+        assert!(solutions.windows(2).all(|w| w[0].len() > w[1].len()));
+        assert!(solutions.iter().all(|s| !s.proven_optimal));
+
+        let last = solutions.last().unwrap();
+        println!("{last}");
+        assert_eq!(last.len(), 10);
+        assert_eq!(last.phase_1().len() + last.phase_2().len(), 10);
+
+        let solved = last
+            .moves
+            .iter()
+            .fold(cube, |cube, &cube_move| cube.apply_cube_move(cube_move));
+        assert_eq!(solved, ReprCube::SOLVED);
 
         Ok(())
     }
@@ -1,16 +1,21 @@
 use std::{fs::create_dir_all, path::Path};
 
+use anyhow::Context;
 use lookup_sym_corner_perm::LookupSymCornerPermTable;
 use lookup_sym_edge_group_orient::LookupSymEdgeGroupOrientTable;
 use memmap2::Mmap;
 use move_raw_corner_orient::MoveRawCornerOrientTable;
 use move_sym_edge_group_orient::MoveSymEdgeGroupOrientTable;
-
-use crate::kociemba::tables::{
-    move_edge_positions::MoveEdgePositionsTable, move_raw_e_edge_perm::MoveRawEEdgePermTable,
-    move_raw_ud_edge_perm::MoveRawUDEdgePermTable, move_sym_corner_perm::MoveSymCornerPermTable,
-    prune_phase_1::PrunePhase1Table, prune_phase_2::PrunePhase2Table,
-    prune_phase_2_corner_sym::PrunePhase2CornerSymTable,
+use table_mapping::{TableMapping, TableMappingReport};
+
+use crate::kociemba::{
+    search::SolveError,
+    tables::{
+        move_edge_positions::MoveEdgePositionsTable, move_raw_e_edge_perm::MoveRawEEdgePermTable,
+        move_raw_ud_edge_perm::MoveRawUDEdgePermTable,
+        move_sym_corner_perm::MoveSymCornerPermTable, prune_phase_1::PrunePhase1Table,
+        prune_phase_2::PrunePhase2Table, prune_phase_2_corner_sym::PrunePhase2CornerSymTable,
+    },
 };
 
 pub mod lookup_sym_corner_perm;
@@ -23,15 +28,13 @@ pub mod move_raw_ud_edge_perm;
 pub mod move_sym_corner_perm;
 pub mod move_sym_edge_group_orient;
 
+pub mod prune_histogram;
 pub mod prune_phase_1;
 pub mod prune_phase_2;
 pub mod prune_phase_2_corner_sym;
 
-// pub mod permuted_coordinates;
-
-// pub mod permute_sym_edge_group_orient;
-
 mod table_loader;
+pub mod table_mapping;
 
 const MOVE_RAW_CORNER_ORIENT_TABLE_NAME: &str = "move_raw_corner_orient_table.dat";
 const MOVE_SYM_EDGE_GROUP_ORIENT_TABLE_NAME: &str = "move_sym_edge_group_orient_table.dat";
@@ -44,8 +47,6 @@ const MOVE_SYM_CORNER_PERM_TABLE_NAME: &str = "move_sym_corner_perm_table.dat";
 const PRUNE_PHASE_2_TABLE_NAME: &str = "prune_phase_2_table.dat";
 const PRUNE_PHASE_2_CORNER_SYM_TABLE_NAME: &str = "prune_phase_2_table_corner_sym.dat";
 const MOVE_EDGE_POSITION_TABLE_NAME: &str = "move_raw_edge_position_table.dat";
-const PERMUTE_SYM_EDGE_GROUP_ORIENT_TABLE_NAME: &str = "permute_sym_edge_group_orient_table.dat";
-const PERMUTE_RAW_CORNER_ORIENT_TABLE_NAME: &str = "permute_raw_corner_orient_table.dat";
 
 struct MovesPreTables {
     lookup_sym_edge_group_orient: Mmap,
@@ -115,7 +116,8 @@ impl MovesPreTables {
         P: AsRef<Path>,
     {
         let folder = folder.as_ref();
-        create_dir_all(folder)?;
+        create_dir_all(folder)
+            .with_context(|| format!("creating the table folder {}", folder.display()))?;
 
         let move_raw_corner_orient =
             MoveRawCornerOrientTable::load(folder.join(MOVE_RAW_CORNER_ORIENT_TABLE_NAME))?;
@@ -191,13 +193,113 @@ impl std::fmt::Debug for Tables {
     }
 }
 
+impl MovesPreTables {
+    fn apply_mapping(self, mapping: TableMapping) -> anyhow::Result<Self> {
+        Ok(Self {
+            lookup_sym_edge_group_orient: mapping.apply(self.lookup_sym_edge_group_orient)?,
+            lookup_sym_corner_perm: mapping.apply(self.lookup_sym_corner_perm)?,
+            move_raw_corner_orient: mapping.apply(self.move_raw_corner_orient)?,
+            move_sym_edge_group_orient: mapping.apply(self.move_sym_edge_group_orient)?,
+            move_sym_corner_perm: mapping.apply(self.move_sym_corner_perm)?,
+            move_edge_position: mapping.apply(self.move_edge_position)?,
+            move_raw_e_edge_perm: mapping.apply(self.move_raw_e_edge_perm)?,
+            move_raw_ud_edge_perm: mapping.apply(self.move_raw_ud_edge_perm)?,
+        })
+    }
+
+    fn mapping_report(&self) -> impl Iterator<Item = TableMappingReport> {
+        [
+            (
+                LOOKUP_SYM_EDGE_GROUP_ORIENT_TABLE_NAME,
+                &self.lookup_sym_edge_group_orient,
+            ),
+            (
+                LOOKUP_SYM_CORNER_PERM_TABLE_NAME,
+                &self.lookup_sym_corner_perm,
+            ),
+            (
+                MOVE_RAW_CORNER_ORIENT_TABLE_NAME,
+                &self.move_raw_corner_orient,
+            ),
+            (
+                MOVE_SYM_EDGE_GROUP_ORIENT_TABLE_NAME,
+                &self.move_sym_edge_group_orient,
+            ),
+            (MOVE_SYM_CORNER_PERM_TABLE_NAME, &self.move_sym_corner_perm),
+            (MOVE_EDGE_POSITION_TABLE_NAME, &self.move_edge_position),
+            (MOVE_E_EDGE_PERM_TABLE_NAME, &self.move_raw_e_edge_perm),
+            (MOVE_UD_EDGE_PERM_TABLE_NAME, &self.move_raw_ud_edge_perm),
+        ]
+        .into_iter()
+        .map(|(name, map)| TableMappingReport::new(name, map))
+    }
+}
+
+impl PrunePreTables {
+    fn apply_mapping(self, mapping: TableMapping) -> anyhow::Result<Self> {
+        Ok(Self {
+            moves_pre_table: self.moves_pre_table.apply_mapping(mapping)?,
+            prune_phase_1: mapping.apply(self.prune_phase_1)?,
+            prune_phase_2: mapping.apply(self.prune_phase_2)?,
+            prune_phase_2_corner_sym: mapping.apply(self.prune_phase_2_corner_sym)?,
+        })
+    }
+
+    fn mapping_report(&self) -> impl Iterator<Item = TableMappingReport> {
+        self.moves_pre_table.mapping_report().chain(
+            [
+                (PRUNE_PHASE_1_TABLE_NAME, &self.prune_phase_1),
+                (PRUNE_PHASE_2_TABLE_NAME, &self.prune_phase_2),
+                (
+                    PRUNE_PHASE_2_CORNER_SYM_TABLE_NAME,
+                    &self.prune_phase_2_corner_sym,
+                ),
+            ]
+            .into_iter()
+            .map(|(name, map)| TableMappingReport::new(name, map)),
+        )
+    }
+}
+
 impl Tables {
-    pub fn new<P>(folder: P) -> anyhow::Result<Self>
+    /// load the tables from `folder`. a table that is missing, has the wrong size or fails its
+    /// checksum is generated again. when that fails too, the error is a `SolveError::Tables`.
+    pub fn new<P>(folder: P) -> Result<Self, SolveError>
     where
         P: AsRef<Path>,
     {
-        let prune_pre_tables = PrunePreTables::new(folder)?;
+        Self::with_mapping(folder, TableMapping::default())
+    }
+
+    /// load the tables, then place them in memory as described by `mapping`.
+    pub fn with_mapping<P>(folder: P, mapping: TableMapping) -> Result<Self, SolveError>
+    where
+        P: AsRef<Path>,
+    {
+        let load = || {
+            let mut prune_pre_tables = PrunePreTables::new(folder)?;
+            if mapping != TableMapping::default() {
+                prune_pre_tables = prune_pre_tables.apply_mapping(mapping)?;
+            }
+            anyhow::Ok(Self { prune_pre_tables })
+        };
+        load().map_err(SolveError::Tables)
+    }
+
+    /// how each table is currently held in memory.
+    pub fn mapping_report(&self) -> Vec<TableMappingReport> {
+        self.prune_pre_tables.mapping_report().collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
 
-        Ok(Self { prune_pre_tables })
+    #[test]
+    fn unusable_folder_is_a_tables_error() {
+        // the folder would have to be inside a file.
+        let tables = Tables::new("Cargo.toml/tables");
+        assert!(matches!(tables, Err(SolveError::Tables(_))));
     }
 }
@@ -1,2 +0,0 @@
-
-mod compute_column_permutations;
\ No newline at end of file
@@ -0,0 +1,95 @@
+use rayon::prelude::*;
+
+/// The largest distance any of the pruning tables can report, plus one.
+const MAX_DISTANCE_COUNT: usize = 32;
+
+/// The distribution of distances stored in a pruning table.
+///
+/// Counts are taken over the stored entries, so each symmetry class is counted once,
+/// regardless of how many cube states it represents.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct PruneHistogram([u64; MAX_DISTANCE_COUNT]);
+
+impl Default for PruneHistogram {
+    fn default() -> Self {
+        Self([0; MAX_DISTANCE_COUNT])
+    }
+}
+
+impl PruneHistogram {
+    pub(crate) fn from_values(values: impl ParallelIterator<Item = u8>) -> Self {
+        values
+            .fold(Self::default, |mut histogram, value| {
+                histogram.0[value as usize] += 1;
+                histogram
+            })
+            .reduce(Self::default, Self::merge)
+    }
+
+    fn merge(mut self, other: Self) -> Self {
+        for (a, b) in self.0.iter_mut().zip(other.0) {
+            *a += b;
+        }
+        self
+    }
+
+    /// the number of entries at exactly `distance`.
+    pub fn count(&self, distance: u8) -> u64 {
+        self.0.get(distance as usize).copied().unwrap_or(0)
+    }
+
+    /// the total number of entries in the table.
+    pub fn total(&self) -> u64 {
+        self.0.iter().sum()
+    }
+
+    /// the largest distance with at least one entry.
+    pub fn max_distance(&self) -> u8 {
+        self.0.iter().rposition(|&count| count != 0).unwrap_or(0) as u8
+    }
+
+    /// the mean value of the heuristic over all entries.
+    pub fn average(&self) -> f64 {
+        let weighted: u64 = self.iter().map(|(d, count)| d as u64 * count).sum();
+        weighted as f64 / self.total() as f64
+    }
+
+    /// all `(distance, count)` pairs from 0 up to the max distance.
+    pub fn iter(&self) -> impl Iterator<Item = (u8, u64)> {
+        (0..=self.max_distance()).map(|d| (d, self.count(d)))
+    }
+}
+
+impl std::fmt::Display for PruneHistogram {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let total = self.total();
+        for (d, count) in self.iter() {
+            writeln!(
+                f,
+                "{d:2}: {count}  ({:.2}%)",
+                100.0 * count as f64 / total as f64
+            )?;
+        }
+        writeln!(f, "total: {total}")?;
+        write!(f, "average: {:.4}", self.average())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rayon::iter::IntoParallelIterator;
+
+    use super::*;
+
+    #[test]
+    fn counts_and_average() {
+        let histogram = PruneHistogram::from_values([0u8, 1, 1, 2, 2, 2, 4].into_par_iter());
+
+        assert_eq!(histogram.total(), 7);
+        assert_eq!(histogram.count(2), 3);
+        assert_eq!(histogram.count(3), 0);
+        assert_eq!(histogram.max_distance(), 4);
+        assert_eq!(histogram.iter().count(), 5);
+        assert!((histogram.average() - 12.0 / 7.0).abs() < 1e-12);
+    }
+}
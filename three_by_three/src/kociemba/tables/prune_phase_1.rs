@@ -18,6 +18,7 @@ use crate::kociemba::tables::lookup_sym_edge_group_orient::LookupSymEdgeGroupOri
 use crate::kociemba::tables::move_raw_corner_orient::MoveRawCornerOrientTable;
 use crate::kociemba::tables::move_sym_edge_group_orient::MoveSymEdgeGroupOrientTable;
 
+use super::prune_histogram::PruneHistogram;
 use super::table_loader::{as_atomic_u8_slice, load_table};
 
 const TABLE_ENTRY_COUNT: usize = 64430 * 2187;
@@ -313,6 +314,15 @@ impl PrunePhase1Table {
 
         val
     }
+
+    /// count how many entries of the table sit at each distance from domino reduction.
+    pub fn histogram(&self) -> PruneHistogram {
+        PruneHistogram::from_values(
+            self.buffer[..TABLE_SIZE_BYTES]
+                .par_iter()
+                .flat_map_iter(|&byte| [byte & 0b1111, byte >> 4]),
+        )
+    }
 }
 
 impl PrunePhase1Table {
@@ -504,6 +514,21 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn histogram() -> anyhow::Result<()> {
+        let tables = Tables::new("tables")?;
+        let table: &PrunePhase1Table = tables.as_ref();
+
+        let histogram = table.histogram();
+        println!("{histogram}");
+
+        assert_eq!(histogram.total(), TABLE_ENTRY_COUNT as u64);
+        assert_eq!(histogram.count(0), 1);
+        assert_eq!(histogram.max_distance(), 12);
+
+        Ok(())
+    }
+
     fn find_single_edge_preserving(
         edge_coord: u16,
         corner_coord: u16,
@@ -19,6 +19,7 @@ use crate::kociemba::tables::lookup_sym_corner_perm::LookupSymCornerPermTable;
 use crate::kociemba::tables::move_raw_ud_edge_perm::MoveRawUDEdgePermTable;
 use crate::kociemba::tables::move_sym_corner_perm::MoveSymCornerPermTable;
 
+use super::prune_histogram::PruneHistogram;
 use super::table_loader::{as_atomic_u8_slice, load_table};
 
 const TABLE_ENTRY_COUNT: usize = 2768 * 40320;
@@ -245,7 +246,10 @@ impl PrunePhase2Table {
             },
             ud_edge_perm_raw_coord,
         };
-        let i = partial.into_index();
+        self.get_value_by_index(partial.into_index())
+    }
+
+    fn get_value_by_index(&self, i: usize) -> u8 {
         PRUNE_TABLE_SHORTCUTS
             .get(&(i as u32))
             .copied()
@@ -256,6 +260,15 @@ impl PrunePhase2Table {
             })
     }
 
+    /// count how many entries of the table sit at each distance from solved.
+    pub fn histogram(&self) -> PruneHistogram {
+        PruneHistogram::from_values(
+            (0..TABLE_ENTRY_COUNT)
+                .into_par_iter()
+                .map(|i| self.get_value_by_index(i)),
+        )
+    }
+
     fn generate(
         buffer: &mut [u8],
         tables: &(
@@ -395,10 +408,26 @@ impl PrunePhase2Table {
 mod test {
     use crate::Tables;
 
+    use super::*;
+
     #[test]
     fn generate() -> anyhow::Result<()> {
         let _tables = Tables::new("tables")?;
 
         Ok(())
     }
+
+    #[test]
+    fn histogram() -> anyhow::Result<()> {
+        let tables = Tables::new("tables")?;
+        let table: &PrunePhase2Table = tables.as_ref();
+
+        let histogram = table.histogram();
+        println!("{histogram}");
+
+        assert_eq!(histogram.total(), TABLE_ENTRY_COUNT as u64);
+        assert_eq!(histogram.count(0), 1);
+
+        Ok(())
+    }
 }
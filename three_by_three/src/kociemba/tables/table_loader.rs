@@ -22,18 +22,24 @@ where
             fs2::FileExt::lock_shared(&file)
                 .with_context(|| format!("locking (shared) {}", path.as_ref().display()))?;
 
-            // SAFETY: the file is at least `size_bytes` if it was generated correctly
-            let mmap = unsafe { MmapOptions::new().len(size_bytes).map(&file)? };
-            let hash_actual = crc32fast::hash(&mmap);
+            // a file of the wrong size is handled like one with a wrong checksum. mapping past
+            // the end of a short file would crash on the first read.
+            let mmap = if file.metadata()?.len() == size_bytes as u64 {
+                // SAFETY: the file is exactly `size_bytes` long
+                let mmap = unsafe { MmapOptions::new().len(size_bytes).map(&file)? };
+                (crc32fast::hash(&mmap) == checksum).then_some(mmap)
+            } else {
+                None
+            };
 
             // we’re done – unlock and return the clean table
             fs2::FileExt::unlock(&file)?;
-            if hash_actual == checksum {
+            if let Some(mmap) = mmap {
                 return Ok(mmap);
             }
 
-            // checksum is wrong – probably somebody crashed half-way.  Drop
-            // shared lock and fall through to the writer branch
+            // size or checksum is wrong – probably somebody crashed half-way.
+            // Drop shared lock and fall through to the writer branch
         }
 
         // ──────────────── 2. regenerate under an exclusive lock ────────────────
@@ -230,3 +236,29 @@ impl<T: Send + Sync + Eq + std::hash::Hash + Ord + Copy + Clone> Iterator for Un
         Some(candidate)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn short_file_is_generated_again() -> Result<()> {
+        let generate = |bytes: &mut [u8]| {
+            for (i, byte) in bytes.iter_mut().enumerate() {
+                *byte = i as u8;
+            }
+        };
+        let mut expected = vec![0; 256];
+        generate(&mut expected);
+        let checksum = crc32fast::hash(&expected);
+
+        // a table cut short, as when its writer was killed.
+        let path = std::env::temp_dir().join(format!("short_table_{}.dat", std::process::id()));
+        std::fs::write(&path, &expected[..100])?;
+        let table = load_table(&path, expected.len(), checksum, generate)?;
+        assert_eq!(&table[..], &expected[..]);
+
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,164 @@
+use memmap2::{Mmap, MmapOptions};
+
+/// How the tables are placed in memory once they are loaded.
+///
+/// The default is a plain read-only mapping of each table file, paged in on first use. The options
+/// trade load time and memory for fewer page faults and TLB misses during the search. They only take
+/// effect on Linux, and are ignored elsewhere.
+///
+/// Pages are faulted in by the thread calling [`Tables::with_mapping`](super::Tables::with_mapping),
+/// so on a NUMA machine they will usually end up on that thread's node.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct TableMapping {
+    /// copy each table into anonymous memory advised with `MADV_HUGEPAGE`, so the kernel can back it
+    /// with transparent huge pages. read-only file mappings only get huge pages when the kernel is
+    /// built with `CONFIG_READ_ONLY_THP_FOR_FS`, so the copy is what makes this reliable.
+    pub huge_pages: bool,
+    /// fault every page in while loading, instead of during the first searches.
+    pub populate: bool,
+    /// `mlock` the tables so they are never paged out. this may need a raised `RLIMIT_MEMLOCK`.
+    pub lock: bool,
+}
+
+impl TableMapping {
+    pub(crate) fn apply(self, map: Mmap) -> anyhow::Result<Mmap> {
+        let map = if self.huge_pages && cfg!(target_os = "linux") {
+            let mut anon = MmapOptions::new().len(map.len()).map_anon()?;
+            #[cfg(target_os = "linux")]
+            anon.advise(memmap2::Advice::HugePage)?;
+            anon.copy_from_slice(&map);
+            anon.make_read_only()?
+        } else {
+            map
+        };
+
+        if self.populate {
+            #[cfg(unix)]
+            map.advise(memmap2::Advice::WillNeed)?;
+            for page in map.chunks(4096) {
+                std::hint::black_box(page[0]);
+            }
+        }
+
+        if self.lock {
+            #[cfg(unix)]
+            map.lock()?;
+        }
+
+        Ok(map)
+    }
+}
+
+/// How much of one table is resident, and how, as reported by `/proc/self/smaps`.
+///
+/// The kernel reports these per mapping, and neighbouring anonymous mappings can be merged into
+/// one, so each value is capped at the size of the table.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct TableMappingReport {
+    pub name: &'static str,
+    pub size_bytes: usize,
+    pub resident_bytes: usize,
+    pub huge_page_bytes: usize,
+    pub locked_bytes: usize,
+}
+
+impl TableMappingReport {
+    pub(crate) fn new(name: &'static str, map: &Mmap) -> Self {
+        let size_bytes = map.len();
+        let mut report = Self {
+            name,
+            size_bytes,
+            resident_bytes: 0,
+            huge_page_bytes: 0,
+            locked_bytes: 0,
+        };
+
+        let Ok(smaps) = std::fs::read_to_string("/proc/self/smaps") else {
+            return report;
+        };
+
+        let start = map.as_ptr() as usize;
+        let end = start + size_bytes;
+        let mut in_table = false;
+
+        for line in smaps.lines() {
+            let mut fields = line.split_whitespace();
+            let Some(first) = fields.next() else {
+                continue;
+            };
+
+            // mapping headers start with the address range, `start-end`, in hex
+            if let Some((low, high)) = first.split_once('-')
+                && let (Ok(low), Ok(high)) = (
+                    usize::from_str_radix(low, 16),
+                    usize::from_str_radix(high, 16),
+                )
+            {
+                in_table = low < end && start < high;
+                continue;
+            }
+
+            if !in_table {
+                continue;
+            }
+
+            let Some(kb) = fields.next().and_then(|kb| kb.parse::<usize>().ok()) else {
+                continue;
+            };
+            match first {
+                "Rss:" => report.resident_bytes += kb * 1024,
+                "AnonHugePages:" | "FilePmdMapped:" => report.huge_page_bytes += kb * 1024,
+                "Locked:" => report.locked_bytes += kb * 1024,
+                _ => {}
+            }
+        }
+
+        report.resident_bytes = report.resident_bytes.min(size_bytes);
+        report.huge_page_bytes = report.huge_page_bytes.min(size_bytes);
+        report.locked_bytes = report.locked_bytes.min(size_bytes);
+
+        report
+    }
+}
+
+impl std::fmt::Display for TableMappingReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mib = |bytes: usize| bytes as f64 / (1024.0 * 1024.0);
+        write!(
+            f,
+            "{:<36} {:8.2} MiB  resident {:8.2} MiB  huge {:8.2} MiB  locked {:8.2} MiB",
+            self.name,
+            mib(self.size_bytes),
+            mib(self.resident_bytes),
+            mib(self.huge_page_bytes),
+            mib(self.locked_bytes),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::kociemba::tables::Tables;
+
+    use super::*;
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn populate_makes_tables_resident() -> anyhow::Result<()> {
+        let tables = Tables::with_mapping(
+            "tables",
+            TableMapping {
+                huge_pages: true,
+                populate: true,
+                lock: false,
+            },
+        )?;
+
+        for report in tables.mapping_report() {
+            println!("{report}");
+            assert_eq!(report.resident_bytes, report.size_bytes, "{}", report.name);
+        }
+
+        Ok(())
+    }
+}
@@ -10,8 +10,14 @@ mod cube_entry;
 mod cube_ops;
 mod kociemba;
 mod permutation_math;
+mod steps;
 
-pub use cube_ops::repr_cube::ReprCube;
+pub use cube_ops::algorithm::{Algorithm, dedup_equivalent};
+pub use cube_ops::cube_axis::Axis;
+pub use cube_ops::cube_sym::CubeSymmetry;
+pub use cube_ops::cycle_structure::{CycleStructure, PieceCycle};
+pub use cube_ops::notation::parse_moves;
+pub use cube_ops::repr_cube::{InvalidCube, ReprCube};
 
 pub use cube_ops::partial_reprs::corner_orient::CornerOrient;
 pub use cube_ops::partial_reprs::corner_perm::CornerPerm;
@@ -23,5 +29,26 @@ pub use permutation_math::permutation::Permutation;
 pub use cube_ops::cube_move::CubeMove;
 
 pub use kociemba::tables::Tables;
+pub use kociemba::tables::prune_histogram::PruneHistogram;
+pub use kociemba::tables::prune_phase_1::PrunePhase1Table;
+pub use kociemba::tables::prune_phase_2::PrunePhase2Table;
+pub use kociemba::tables::table_mapping::{TableMapping, TableMappingReport};
 
-pub use kociemba::search::get_incremental_solutions_stream;
+pub use kociemba::search::{
+    DominoReduction, DominoReductionOptions, SearchEvent, SearchStream, SearchUpdate, Solution,
+    SolutionIter, SolutionStream, SolutionUpdate, SolveError, SolveOptions, ThreadThroughput,
+    domino_reductions, get_incremental_solutions_stream, get_incremental_solutions_stream_in,
+    get_search_updates_stream, solve, solve_iter,
+};
+
+pub use steps::algorithm_generator::{
+    AlgorithmGenerator, Ergonomics, FaceWeights, GeneratedAlgorithm, MoveSet,
+};
+pub use steps::bld::{BldOptions, LetteringScheme, Memo, Sticker};
+pub use steps::commutator::{Commutator, CommutatorFinder};
+pub use steps::eo::{EoCase, EoGoal, EoTables};
+pub use steps::goal::{Goal, GoalSolver, MAX_GOAL_LEN};
+pub use steps::insertion::{INSERTION_LIBRARY, Insertion, InsertionFinder};
+pub use steps::last_layer::{LlCase, LlRecognizer, LlSet, RecognizeError};
+pub use steps::niss::{NissSolution, SearchDirection};
+pub use steps::reconstruction::{Method, Reconstruction, ReconstructionStep, Reconstructor};
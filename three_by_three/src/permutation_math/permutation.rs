@@ -154,6 +154,64 @@ impl<const N: usize> Permutation<N> {
         parity == 1
     }
 
+    /// the cycles with more than one element, each starting from its smallest element and in the
+    /// order the elements move: the element in slot `cycle[0]` moves to slot `cycle[1]`.
+    pub fn cycles(self) -> Vec<Vec<u8>> {
+        let inv = self.invert().0;
+        let mut seen = [false; N];
+        let mut cycles = Vec::new();
+
+        for start in 0..N {
+            if seen[start] || inv[start] as usize == start {
+                continue;
+            }
+            let mut cycle = Vec::new();
+            let mut i = start;
+            while !seen[i] {
+                seen[i] = true;
+                cycle.push(i as u8);
+                i = inv[i] as usize;
+            }
+            cycles.push(cycle);
+        }
+        cycles
+    }
+
+    /// the lengths of all the cycles, fixed elements included, longest first. two permutations are
+    /// conjugate exactly when they have the same cycle type.
+    pub fn cycle_type(self) -> Vec<u8> {
+        let cycles = self.cycles();
+        let fixed = N - cycles.iter().map(Vec::len).sum::<usize>();
+        let mut lengths = cycles
+            .iter()
+            .map(|cycle| cycle.len() as u8)
+            .chain(std::iter::repeat_n(1, fixed))
+            .collect::<Vec<_>>();
+        lengths.sort_unstable_by(|a, b| b.cmp(a));
+        lengths
+    }
+
+    /// the smallest number of times the permutation has to be repeated to get back to the identity.
+    pub fn order(self) -> u64 {
+        self.cycles()
+            .iter()
+            .fold(1, |order, cycle| lcm(order, cycle.len() as u64))
+    }
+
+    /// the permutation repeated `k` times.
+    pub const fn pow(self, mut k: u32) -> Self {
+        let mut out = Self::IDENTITY;
+        let mut square = self;
+        while k > 0 {
+            if k & 1 == 1 {
+                out = out.then(square);
+            }
+            square = square.then(square);
+            k >>= 1;
+        }
+        out
+    }
+
     pub const fn const_eq(self, other: Self) -> bool {
         let mut i = 1;
         while i < N {
@@ -166,6 +224,17 @@ impl<const N: usize> Permutation<N> {
     }
 }
 
+pub(crate) const fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+pub(crate) const fn lcm(a: u64, b: u64) -> u64 {
+    a / gcd(a, b) * b
+}
+
 impl<const N: usize> Distribution<Permutation<N>> for StandardUniform {
     fn sample<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> Permutation<N> {
         let mut out = Permutation::IDENTITY;
@@ -245,4 +314,37 @@ mod tests {
             assert_eq!(p.invert().invert(), p);
         }
     }
+
+    #[test]
+    fn cycles_and_order() {
+        // 0 moves to 2, 2 moves to 4 and 4 moves to 0.
+        let p = Permutation::<6>([4, 1, 0, 5, 2, 3]);
+        assert_eq!(p.cycles(), vec![vec![0, 2, 4], vec![3, 5]]);
+        assert_eq!(p.cycle_type(), vec![3, 2, 1]);
+        assert_eq!(p.order(), 6);
+
+        assert_eq!(Permutation::<4>::IDENTITY.cycles(), Vec::<Vec<u8>>::new());
+        assert_eq!(Permutation::<4>::IDENTITY.cycle_type(), vec![1; 4]);
+        assert_eq!(Permutation::<4>::IDENTITY.order(), 1);
+
+        let mut repeated = Permutation::IDENTITY;
+        for k in 0..8 {
+            assert_eq!(p.pow(k), repeated);
+            assert_eq!(
+                p.pow(k) == Permutation::IDENTITY,
+                (k as u64).is_multiple_of(p.order())
+            );
+            repeated = repeated.then(p);
+        }
+
+        // following the cycles moves the elements the same way `apply_to` does.
+        let mut moved = [0, 1, 2, 3, 4, 5];
+        p.apply_to(&mut moved);
+        for cycle in p.cycles() {
+            for (i, &slot) in cycle.iter().enumerate() {
+                let next = cycle[(i + 1) % cycle.len()];
+                assert_eq!(moved[next as usize], slot);
+            }
+        }
+    }
 }
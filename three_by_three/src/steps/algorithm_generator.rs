@@ -0,0 +1,296 @@
+use std::collections::HashSet;
+
+use anyhow::anyhow;
+
+use crate::{
+    cube_ops::{cube_move::CubeMove, cube_prev_axis::CubePreviousAxis, repr_cube::ReprCube},
+    kociemba::tables::Tables,
+    steps::{
+        goal::{Goal, GoalSolver, Heuristic},
+        last_layer::{auf, auf_move},
+    },
+};
+
+/// The faces an algorithm may turn, e.g. `<R, U, F>`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct MoveSet {
+    // bit `i` is face `i` in the order of `CubeMove`: U D F B R L.
+    faces: u8,
+}
+
+impl MoveSet {
+    pub const RU: MoveSet = MoveSet { faces: 0b01_0001 };
+    pub const RUF: MoveSet = MoveSet { faces: 0b01_0101 };
+    pub const RUD: MoveSet = MoveSet { faces: 0b01_0011 };
+    pub const RUL: MoveSet = MoveSet { faces: 0b11_0001 };
+
+    /// the move set of the faces named in `faces`, e.g. `"RUF"`.
+    pub fn new(faces: &str) -> anyhow::Result<Self> {
+        let faces = faces.chars().try_fold(0, |set, face| {
+            let i = "UDFBRL"
+                .find(face)
+                .ok_or_else(|| anyhow!("{face:?} is not a face"))?;
+            anyhow::Ok(set | (1 << i))
+        })?;
+        Ok(Self { faces })
+    }
+
+    pub fn contains(self, mv: CubeMove) -> bool {
+        self.faces & (1 << (mv as u8 / 3)) != 0
+    }
+}
+
+/// Scores how easy an algorithm is to perform, lower is better.
+pub trait Ergonomics {
+    fn score(&self, moves: &[CubeMove]) -> f64;
+}
+
+impl<F: Fn(&[CubeMove]) -> f64> Ergonomics for F {
+    fn score(&self, moves: &[CubeMove]) -> f64 {
+        self(moves)
+    }
+}
+
+/// A cost for each face turn, by face, with extra for half turns.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct FaceWeights {
+    /// in the order of `CubeMove`: U D F B R L.
+    pub faces: [f64; 6],
+    pub half_turn: f64,
+}
+
+impl Default for FaceWeights {
+    fn default() -> Self {
+        Self {
+            faces: [1.0, 1.4, 1.5, 2.0, 1.0, 1.3],
+            half_turn: 0.3,
+        }
+    }
+}
+
+impl Ergonomics for FaceWeights {
+    fn score(&self, moves: &[CubeMove]) -> f64 {
+        moves
+            .iter()
+            .map(|&mv| {
+                let half_turn = if mv as u8 % 3 == 1 {
+                    self.half_turn
+                } else {
+                    0.0
+                };
+                self.faces[mv as usize / 3] + half_turn
+            })
+            .sum()
+    }
+}
+
+/// An algorithm found by `AlgorithmGenerator`. `pre_auf`, then `moves`, then `post_auf` solve the
+/// case.
+#[derive(Clone, PartialEq, Debug)]
+pub struct GeneratedAlgorithm {
+    pub moves: Vec<CubeMove>,
+    pub pre_auf: Option<CubeMove>,
+    pub post_auf: Option<CubeMove>,
+    pub score: f64,
+}
+
+/// Finds every algorithm for a case within a move set and a length.
+///
+/// The heuristic is the pattern databases of the F2L pieces, which have to end up solved whatever
+/// the AUF, and with `Tables` also the domino distances of the Kociemba search.
+#[derive(Default)]
+pub struct AlgorithmGenerator<'a> {
+    goals: GoalSolver,
+    tables: Option<&'a Tables>,
+}
+
+impl<'a> AlgorithmGenerator<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_tables(tables: &'a Tables) -> Self {
+        Self {
+            goals: GoalSolver::new(),
+            tables: Some(tables),
+        }
+    }
+
+    /// every algorithm of at most `max_len` moves from `move_set` that solves `case` up to AUF,
+    /// best score first.
+    ///
+    /// algorithms don't start or end with a U turn, since those are the AUFs, and don't pass through
+    /// a solved cube.
+    pub fn generate(
+        &self,
+        case: ReprCube,
+        move_set: MoveSet,
+        max_len: usize,
+        ergonomics: &impl Ergonomics,
+    ) -> Vec<GeneratedAlgorithm> {
+        let search = Search {
+            f2l: self.goals.heuristic(Goal::F2L),
+            tables: self.tables,
+            move_set,
+        };
+        let mut seen = HashSet::new();
+        let mut algorithms = Vec::new();
+
+        for pre in 0..4 {
+            let mut found = Vec::new();
+            search.collect(
+                case.then(auf(pre)),
+                max_len,
+                CubePreviousAxis::None,
+                &mut Vec::new(),
+                &mut found,
+            );
+            for (moves, post) in found {
+                // a symmetric case can be solved by the same moves after two AUFs.
+                if seen.insert(moves.clone()) {
+                    algorithms.push(GeneratedAlgorithm {
+                        score: ergonomics.score(&moves),
+                        moves,
+                        pre_auf: auf_move(pre),
+                        post_auf: auf_move(post),
+                    });
+                }
+            }
+        }
+
+        algorithms.sort_by(|a, b| {
+            a.score
+                .total_cmp(&b.score)
+                .then(a.moves.len().cmp(&b.moves.len()))
+        });
+        algorithms
+    }
+}
+
+struct Search<'a> {
+    f2l: Heuristic,
+    tables: Option<&'a Tables>,
+    move_set: MoveSet,
+}
+
+impl Search<'_> {
+    // the AUF that solves `cube`, if there is one.
+    fn post_auf(cube: ReprCube) -> Option<u8> {
+        (0..4).find(|&post| cube.then(auf(post)) == ReprCube::SOLVED)
+    }
+
+    fn heuristic(&self, cube: ReprCube) -> usize {
+        let f2l = self.f2l.distance(cube) as usize;
+        // an algorithm for `cube` ending in an AUF of `post` solves `auf(post).then(cube)`.
+        let tables = self.tables.map_or(0, |tables| {
+            (0..4)
+                .map(|post| tables.lower_bound(auf(post).then(cube)))
+                .min()
+                .unwrap() as usize
+        });
+        f2l.max(tables)
+    }
+
+    fn collect(
+        &self,
+        cube: ReprCube,
+        remaining: usize,
+        prev_axis: CubePreviousAxis,
+        moves: &mut Vec<CubeMove>,
+        found: &mut Vec<(Vec<CubeMove>, u8)>,
+    ) {
+        if !moves.is_empty()
+            && let Some(post) = Self::post_auf(cube)
+        {
+            found.push((moves.clone(), post));
+            return;
+        }
+        if remaining == 0 || self.heuristic(cube) > remaining {
+            return;
+        }
+
+        for mv in CubeMove::new_axis_iter(prev_axis, false) {
+            let is_auf = mv as u8 / 3 == 0 && moves.is_empty();
+            if !self.move_set.contains(mv) || is_auf {
+                continue;
+            }
+            moves.push(mv);
+            self.collect(
+                cube.apply_move(mv),
+                remaining - 1,
+                prev_axis.update_with_new_move(mv, remaining as u8),
+                moves,
+                found,
+            );
+            moves.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{cube_ops::notation::parse_moves, steps::last_layer::inverse};
+
+    use super::*;
+
+    fn solves(case: ReprCube, algorithm: &GeneratedAlgorithm) -> bool {
+        let moves = algorithm
+            .pre_auf
+            .into_iter()
+            .chain(algorithm.moves.iter().copied())
+            .chain(algorithm.post_auf);
+        moves.fold(case, |cube, mv| cube.apply_move(mv)) == ReprCube::SOLVED
+    }
+
+    #[test]
+    fn generate_algorithms() -> anyhow::Result<()> {
+        let generator = AlgorithmGenerator::new();
+        let sune = parse_moves("R U R' U R U2 R'")?;
+        let case = inverse(&sune);
+
+        let algorithms = generator.generate(case, MoveSet::RU, 7, &FaceWeights::default());
+        assert!(algorithms.iter().any(|algorithm| algorithm.moves == sune));
+        for algorithm in &algorithms {
+            assert!(solves(case, algorithm), "{algorithm:?}");
+            assert!(algorithm.moves.len() <= 7);
+            assert!(algorithm.moves.iter().all(|&mv| MoveSet::RU.contains(mv)));
+            assert_ne!(algorithm.moves[0] as u8 / 3, 0);
+        }
+        assert!(algorithms.windows(2).all(|w| w[0].score <= w[1].score));
+
+        // a custom score that avoids F turns.
+        let oll_45 = parse_moves("F R U R' U' F'")?;
+        let case = inverse(&oll_45);
+        let no_f = |moves: &[CubeMove]| {
+            moves.len() as f64 + 10.0 * moves.iter().filter(|&&mv| mv as u8 / 3 == 2).count() as f64
+        };
+        let algorithms = generator.generate(case, MoveSet::new("RUF")?, 8, &no_f);
+        assert!(algorithms.iter().any(|algorithm| algorithm.moves == oll_45));
+        assert!(algorithms.iter().all(|algorithm| solves(case, algorithm)));
+        assert!(algorithms[0].score <= no_f(&oll_45));
+
+        // the generator finds nothing shorter than the case needs.
+        assert!(generator.generate(case, MoveSet::RUF, 5, &no_f).is_empty());
+        assert!(MoveSet::new("RUX").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn generate_with_tables() -> anyhow::Result<()> {
+        let tables = Tables::new("tables")?;
+        let case = inverse(&parse_moves("R U R' U R U2 R'")?);
+
+        let without =
+            AlgorithmGenerator::new().generate(case, MoveSet::RU, 9, &FaceWeights::default());
+        let with = AlgorithmGenerator::with_tables(&tables).generate(
+            case,
+            MoveSet::RU,
+            9,
+            &FaceWeights::default(),
+        );
+        assert_eq!(with, without);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,436 @@
+use anyhow::{anyhow, bail};
+
+use crate::cube_ops::{
+    partial_reprs::{
+        corner_orient::CornerOrient, corner_perm::CornerPerm, edge_orient::EdgeOrient,
+        edge_perm::EdgePerm,
+    },
+    repr_cube::{InvalidCube, ReprCube},
+};
+use crate::permutation_math::permutation::Permutation;
+
+/// One sticker of a corner or an edge slot.
+///
+/// `facelet` counts clockwise around a corner from its U or D sticker, and around an edge from its
+/// U or D sticker, or its F or B sticker for the edges of the E slice. a piece with orientation `o`
+/// has its own sticker `k` on `facelet` `k + o` of its slot.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct Sticker {
+    pub slot: u8,
+    pub facelet: u8,
+}
+
+impl Sticker {
+    pub const fn new(slot: u8, facelet: u8) -> Self {
+        Self { slot, facelet }
+    }
+}
+
+/// The letters a blindfolded solver gives to the corner and edge stickers.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct LetteringScheme {
+    corners: [[char; 3]; 8],
+    edges: [[char; 2]; 12],
+}
+
+impl LetteringScheme {
+    /// the Speffz scheme, which letters each face A to X clockwise from the top left, in the order
+    /// U L F R B D.
+    pub const SPEFFZ: Self = Self {
+        corners: [
+            ['C', 'M', 'J'],
+            ['D', 'I', 'F'],
+            ['B', 'Q', 'N'],
+            ['A', 'E', 'R'],
+            ['V', 'K', 'P'],
+            ['U', 'G', 'L'],
+            ['W', 'O', 'T'],
+            ['X', 'S', 'H'],
+        ],
+        edges: [
+            ['C', 'I'],
+            ['A', 'Q'],
+            ['B', 'M'],
+            ['D', 'E'],
+            ['U', 'K'],
+            ['W', 'S'],
+            ['V', 'O'],
+            ['X', 'G'],
+            ['J', 'P'],
+            ['L', 'F'],
+            ['T', 'N'],
+            ['R', 'H'],
+        ],
+    };
+
+    /// a scheme with the 24 letters of `corners` and `edges` on the stickers that Speffz letters A
+    /// to X.
+    pub fn new(corners: &str, edges: &str) -> anyhow::Result<Self> {
+        let corners = relabel(&Self::SPEFFZ.corners, corners)?;
+        let edges = relabel(&Self::SPEFFZ.edges, edges)?;
+        Ok(Self { corners, edges })
+    }
+
+    pub fn corner_letter(&self, sticker: Sticker) -> char {
+        self.corners[sticker.slot as usize][sticker.facelet as usize]
+    }
+
+    pub fn edge_letter(&self, sticker: Sticker) -> char {
+        self.edges[sticker.slot as usize][sticker.facelet as usize]
+    }
+
+    pub fn corner_sticker(&self, letter: char) -> Option<Sticker> {
+        find(&self.corners, letter)
+    }
+
+    pub fn edge_sticker(&self, letter: char) -> Option<Sticker> {
+        find(&self.edges, letter)
+    }
+}
+
+impl Default for LetteringScheme {
+    fn default() -> Self {
+        Self::SPEFFZ
+    }
+}
+
+// move each Speffz letter of `speffz` to the letter at its position in `letters`.
+fn relabel<const N: usize, const F: usize>(
+    speffz: &[[char; F]; N],
+    letters: &str,
+) -> anyhow::Result<[[char; F]; N]> {
+    let letters = letters.chars().collect::<Vec<_>>();
+    if letters.len() != N * F {
+        bail!("a scheme needs {} letters, not {}", N * F, letters.len());
+    }
+    if (1..letters.len()).any(|i| letters[..i].contains(&letters[i])) {
+        bail!("the letters of a scheme must be different");
+    }
+    Ok(speffz.map(|stickers| stickers.map(|c| letters[(c as u8 - b'A') as usize])))
+}
+
+fn find<const N: usize, const F: usize>(letters: &[[char; F]; N], letter: char) -> Option<Sticker> {
+    (0..N).find_map(|slot| {
+        let facelet = letters[slot].iter().position(|&c| c == letter)?;
+        Some(Sticker::new(slot as u8, facelet as u8))
+    })
+}
+
+/// The buffers and lettering of a blindfolded method.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BldOptions {
+    pub scheme: LetteringScheme,
+    pub corner_buffer: Sticker,
+    pub edge_buffer: Sticker,
+}
+
+impl Default for BldOptions {
+    /// Speffz with the UFR and UF buffers.
+    fn default() -> Self {
+        Self {
+            scheme: LetteringScheme::SPEFFZ,
+            corner_buffer: Sticker::new(0, 0),
+            edge_buffer: Sticker::new(0, 0),
+        }
+    }
+}
+
+/// The letters a blindfolded solver memorizes for a cube.
+///
+/// `corners` and `edges` are the targets of the buffers, cycle breaks included. `twists` and
+/// `flips` are the pieces left in their slots but not oriented, named by the sticker their U or D
+/// (or F or B) sticker is on.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct Memo {
+    pub edges: Vec<char>,
+    pub corners: Vec<char>,
+    pub flips: Vec<char>,
+    pub twists: Vec<char>,
+}
+
+impl Memo {
+    /// whether the buffers have to be swapped before the corners, because the corners take an odd
+    /// number of targets. the edges then take an odd number too, as both follow the parity of the
+    /// permutation.
+    pub fn parity(&self) -> bool {
+        self.corners.len() % 2 == 1
+    }
+}
+
+impl std::fmt::Display for Memo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let pairs = |letters: &[char]| {
+            letters
+                .chunks(2)
+                .map(|pair| pair.iter().collect::<String>())
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+
+        writeln!(f, "edges: {}", pairs(&self.edges))?;
+        write!(f, "corners: {}", pairs(&self.corners))?;
+        if !self.flips.is_empty() {
+            write!(f, "\nflips: {}", pairs(&self.flips))?;
+        }
+        if !self.twists.is_empty() {
+            write!(f, "\ntwists: {}", pairs(&self.twists))?;
+        }
+        if self.parity() {
+            write!(f, "\nparity")?;
+        }
+        Ok(())
+    }
+}
+
+impl BldOptions {
+    /// the memo of `cube`, traced from the buffers.
+    ///
+    /// each target is where the sticker on the buffer belongs. when the buffer piece comes back
+    /// while other pieces are unsolved, the cycle is broken into the unsolved piece with the first
+    /// letter in alphabetical order.
+    pub fn memo(&self, cube: ReprCube) -> Result<Memo, InvalidCube> {
+        cube.validate()?;
+
+        let scheme = &self.scheme;
+        let (corners, twists) = trace::<8, 3>(
+            cube.corner_perm.0.0,
+            cube.corner_orient.0,
+            self.corner_buffer,
+            |sticker| scheme.corner_letter(sticker),
+        );
+        let (edges, flips) = trace::<12, 2>(
+            cube.edge_perm.0.0,
+            cube.edge_orient.0,
+            self.edge_buffer,
+            |sticker| scheme.edge_letter(sticker),
+        );
+
+        Ok(Memo {
+            edges,
+            corners,
+            flips,
+            twists,
+        })
+    }
+
+    /// the cube that `memo` solves, the reverse of `memo`.
+    pub fn execute(&self, memo: &Memo) -> anyhow::Result<ReprCube> {
+        let scheme = &self.scheme;
+        let (corner_perm, corner_orient) =
+            untrace::<8, 3>(&memo.corners, &memo.twists, self.corner_buffer, |letter| {
+                scheme.corner_sticker(letter)
+            })?;
+        let (edge_perm, edge_orient) =
+            untrace::<12, 2>(&memo.edges, &memo.flips, self.edge_buffer, |letter| {
+                scheme.edge_sticker(letter)
+            })?;
+
+        let cube = ReprCube {
+            corner_perm: CornerPerm(Permutation::const_from_array(corner_perm)),
+            corner_orient: CornerOrient(corner_orient),
+            edge_perm: EdgePerm(Permutation::const_from_array(edge_perm)),
+            edge_orient: EdgeOrient(edge_orient),
+        };
+        cube.validate()?;
+        Ok(cube)
+    }
+}
+
+// swap the sticker on the buffer with the sticker on `target`, along with their pieces. this undoes
+// itself.
+fn swap<const N: usize, const F: u8>(
+    perm: &mut [u8; N],
+    orient: &mut [u8; N],
+    buffer: Sticker,
+    target: Sticker,
+) {
+    let (b, t) = (buffer.slot as usize, target.slot as usize);
+    let turn = F + target.facelet - buffer.facelet;
+    (orient[b], orient[t]) = ((orient[t] + F - turn % F) % F, (orient[b] + turn) % F);
+    perm.swap(b, t);
+}
+
+// the targets and the misoriented pieces of one kind of piece.
+fn trace<const N: usize, const F: u8>(
+    mut perm: [u8; N],
+    mut orient: [u8; N],
+    buffer: Sticker,
+    letter: impl Fn(Sticker) -> char,
+) -> (Vec<char>, Vec<char>) {
+    let b = buffer.slot as usize;
+    let mut targets = Vec::new();
+
+    loop {
+        let piece = perm[b];
+        let target = if piece as usize != b {
+            // the sticker on the buffer, in the slot it belongs to.
+            Sticker::new(piece, (F + buffer.facelet - orient[b]) % F)
+        } else {
+            let unsolved = (0..N).filter(|&slot| slot != b && perm[slot] as usize != slot);
+            let cycle_break = unsolved
+                .flat_map(|slot| (0..F).map(move |facelet| Sticker::new(slot as u8, facelet)))
+                .min_by_key(|&sticker| letter(sticker));
+            match cycle_break {
+                Some(sticker) => sticker,
+                None => break,
+            }
+        };
+        targets.push(letter(target));
+        swap::<N, F>(&mut perm, &mut orient, buffer, target);
+    }
+
+    let misoriented = (0..N)
+        .filter(|&slot| slot != b && orient[slot] != 0)
+        .map(|slot| letter(Sticker::new(slot as u8, orient[slot])))
+        .collect();
+    (targets, misoriented)
+}
+
+// the pieces of one kind that `targets` and `misoriented` solve.
+fn untrace<const N: usize, const F: u8>(
+    targets: &[char],
+    misoriented: &[char],
+    buffer: Sticker,
+    sticker: impl Fn(char) -> Option<Sticker>,
+) -> anyhow::Result<([u8; N], [u8; N])> {
+    let sticker = |letter| sticker(letter).ok_or_else(|| anyhow!("{letter:?} isn't a sticker"));
+    let b = buffer.slot as usize;
+    let mut perm = std::array::from_fn(|slot| slot as u8);
+    let mut orient = [0; N];
+
+    for &letter in misoriented {
+        let Sticker { slot, facelet } = sticker(letter)?;
+        if slot as usize == b || facelet == 0 {
+            bail!("{letter:?} can't be a misoriented piece");
+        }
+        orient[slot as usize] = facelet;
+    }
+    let total = orient.iter().map(|&o| o as usize).sum::<usize>();
+    orient[b] = ((F as usize - total % F as usize) % F as usize) as u8;
+
+    for &letter in targets.iter().rev() {
+        let target = sticker(letter)?;
+        if target.slot as usize == b {
+            bail!("{letter:?} is on the buffer");
+        }
+        swap::<N, F>(&mut perm, &mut orient, buffer, target);
+    }
+    Ok((perm, orient))
+}
+
+#[cfg(test)]
+mod test {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use crate::cube;
+
+    use super::*;
+
+    #[test]
+    fn memo_single_move() -> anyhow::Result<()> {
+        let options = BldOptions::default();
+
+        let memo = options.memo(cube![R])?;
+        assert_eq!(memo.corners, vec!['K', 'W', 'Q']);
+        // the UF buffer is solved, so the edges start with a cycle break into UR.
+        assert_eq!(memo.edges, vec!['B', 'J', 'V', 'T', 'B']);
+        assert!(memo.parity());
+        assert_eq!(memo.to_string(), "edges: BJ VT B\ncorners: KW Q\nparity");
+
+        assert_eq!(options.memo(ReprCube::SOLVED)?, Memo::default());
+        assert!(!options.memo(cube![R2])?.parity());
+
+        Ok(())
+    }
+
+    #[test]
+    fn twists_and_flips() -> anyhow::Result<()> {
+        let options = BldOptions::default();
+
+        // UFL twisted clockwise and UBL counterclockwise.
+        let cube = ReprCube {
+            corner_orient: CornerOrient([0, 1, 0, 2, 0, 0, 0, 0]),
+            ..ReprCube::SOLVED
+        };
+        let memo = options.memo(cube)?;
+        assert!(memo.corners.is_empty() && memo.edges.is_empty());
+        assert_eq!(memo.twists, vec!['I', 'R']);
+        assert_eq!(options.execute(&memo)?, cube);
+
+        // UF and UR flipped. the flip of the buffer is left implied.
+        let cube = ReprCube {
+            edge_orient: EdgeOrient([1, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0]),
+            ..ReprCube::SOLVED
+        };
+        let memo = options.memo(cube)?;
+        assert_eq!(memo.flips, vec!['M']);
+        assert_eq!(options.execute(&memo)?, cube);
+
+        let df_buffer = BldOptions {
+            edge_buffer: Sticker::new(4, 0),
+            ..options
+        };
+        let memo = df_buffer.memo(cube)?;
+        assert!(memo.edges.is_empty());
+        assert_eq!(memo.flips, vec!['I', 'M']);
+        assert_eq!(df_buffer.execute(&memo)?, cube);
+
+        // a flipped buffer in a cycle.
+        let cube = cube![R].then(ReprCube {
+            edge_orient: EdgeOrient([1, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0]),
+            ..ReprCube::SOLVED
+        });
+        let memo = options.memo(cube)?;
+        assert_eq!(options.execute(&memo)?, cube);
+
+        Ok(())
+    }
+
+    #[test]
+    fn execute_reverses_memo() -> anyhow::Result<()> {
+        // old pochmann buffers UBL and UR, and a scheme with the letters in reverse.
+        let reversed = "XWVUTSRQPONMLKJIHGFEDCBA";
+        let options = [
+            BldOptions::default(),
+            BldOptions {
+                scheme: LetteringScheme::new(reversed, reversed)?,
+                corner_buffer: Sticker::new(3, 0),
+                edge_buffer: Sticker::new(2, 0),
+            },
+        ];
+
+        let mut rng = ChaCha8Rng::seed_from_u64(42);
+        for _ in 0..200 {
+            let cube: ReprCube =
+                rand::distr::Distribution::sample(&rand::distr::StandardUniform, &mut rng);
+            for options in &options {
+                let memo = options.memo(cube)?;
+                assert_eq!(options.execute(&memo)?, cube, "{memo}");
+                assert_eq!(memo.parity(), memo.edges.len() % 2 == 1);
+            }
+        }
+
+        let options = BldOptions::default();
+        assert!(LetteringScheme::new("ABC", reversed).is_err());
+        assert!(LetteringScheme::new(&"A".repeat(24), reversed).is_err());
+        assert!(
+            options
+                .execute(&Memo {
+                    corners: vec!['C'],
+                    ..Memo::default()
+                })
+                .is_err()
+        );
+        assert!(
+            options
+                .execute(&Memo {
+                    corners: vec!['K'],
+                    ..Memo::default()
+                })
+                .is_err()
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,223 @@
+use std::collections::HashMap;
+
+use crate::cube_ops::{
+    algorithm::Algorithm, cube_move::CubeMove, cube_prev_axis::CubePreviousAxis,
+    repr_cube::ReprCube,
+};
+
+/// A conjugated commutator `[setup: [a, b]]`, which is `setup a b a' b' setup'`.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Commutator {
+    pub setup: Algorithm,
+    pub a: Algorithm,
+    pub b: Algorithm,
+}
+
+impl Commutator {
+    /// the moves the commutator expands into, with cancellations.
+    pub fn algorithm(&self) -> Algorithm {
+        let setup = &self.setup.moves;
+        let inner = [&self.a, &self.b, &self.a.inverse(), &self.b.inverse()];
+        let moves = setup
+            .iter()
+            .chain(inner.into_iter().flat_map(|part| &part.moves))
+            .chain(&self.setup.inverse().moves)
+            .copied()
+            .collect();
+        Algorithm::new(moves).simplify()
+    }
+}
+
+/// `[a, b]`, or `[setup: [a, b]]` with a setup.
+impl std::fmt::Display for Commutator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.setup.is_empty() {
+            write!(f, "[{}, {}]", self.a, self.b)
+        } else {
+            write!(f, "[{}: [{}, {}]]", self.setup, self.a, self.b)
+        }
+    }
+}
+
+/// Finds the commutators that cycle three corners or three edges.
+///
+/// every commutator `[a, b]` with `a` and `b` together at most `max_len` moves is enumerated once,
+/// and the ones that are 3-cycles are kept by the cube they make.
+pub struct CommutatorFinder {
+    commutators: HashMap<ReprCube, Vec<(Algorithm, Algorithm)>>,
+}
+
+impl CommutatorFinder {
+    pub fn new(max_len: usize) -> Self {
+        let sequences = sequences(max_len.saturating_sub(1));
+        // the sequences by length, so each `a` only goes through the `b`s short enough for it.
+        let mut by_len = vec![Vec::new(); max_len.max(1)];
+        for sequence in &sequences {
+            by_len[sequence.len()].push((sequence, sequence.cube()));
+        }
+        let mut commutators: HashMap<_, Vec<_>> = HashMap::new();
+
+        for a in &sequences {
+            let a_cube = a.cube();
+            let a_inverse = a_cube.inverse();
+            for &(b, b_cube) in by_len[..=max_len - a.len()].iter().flatten() {
+                let cube = a_cube.then(b_cube).then(a_inverse).then(b_cube.inverse());
+                if is_three_cycle(cube) {
+                    commutators
+                        .entry(cube)
+                        .or_default()
+                        .push((a.clone(), b.clone()));
+                }
+            }
+        }
+
+        Self { commutators }
+    }
+
+    /// the commutators, with setups of at most `max_setup_len` moves, that make `case`. shortest
+    /// first, after cancellations.
+    ///
+    /// cases that aren't a 3-cycle of corners or of edges have none.
+    pub fn find(&self, case: ReprCube, max_setup_len: usize) -> Vec<Commutator> {
+        let mut found = Vec::new();
+        if !is_three_cycle(case) {
+            return found;
+        }
+
+        let setups = std::iter::once(Algorithm::default()).chain(sequences(max_setup_len));
+        for setup in setups {
+            // `setup x setup'` is `case` when `x` is this.
+            let setup_cube = setup.cube();
+            let inner = setup_cube.inverse().then(case).then(setup_cube);
+            for (a, b) in self.commutators.get(&inner).into_iter().flatten() {
+                found.push(Commutator {
+                    setup: setup.clone(),
+                    a: a.clone(),
+                    b: b.clone(),
+                });
+            }
+        }
+
+        found.sort_by_cached_key(|commutator| {
+            (commutator.algorithm().len(), commutator.setup.len())
+        });
+        found
+    }
+}
+
+// whether `cube` moves exactly three corners or three edges, and nothing else.
+fn is_three_cycle(cube: ReprCube) -> bool {
+    // the pieces out of place or misoriented, counted first since most cubes fail on that.
+    let corners = (0..8)
+        .filter(|&i| cube.corner_perm.0.0[i] != i as u8 || cube.corner_orient.0[i] != 0)
+        .count();
+    let edges = (0..12)
+        .filter(|&i| cube.edge_perm.0.0[i] != i as u8 || cube.edge_orient.0[i] != 0)
+        .count();
+    if !matches!((corners, edges), (3, 0) | (0, 3)) {
+        return false;
+    }
+
+    let cycles = cube.cycle_structure();
+    let mut all = cycles.corners.iter().chain(&cycles.edges);
+    matches!((all.next(), all.next()), (Some(cycle), None) if cycle.slots.len() == 3)
+}
+
+// every move sequence of 1 to `max_len` moves without two turns of a face in a row or opposite
+// faces out of order.
+fn sequences(max_len: usize) -> Vec<Algorithm> {
+    fn extend(
+        moves: &mut Vec<CubeMove>,
+        prev_axis: CubePreviousAxis,
+        remaining: usize,
+        out: &mut Vec<Algorithm>,
+    ) {
+        if !moves.is_empty() {
+            out.push(Algorithm::new(moves.clone()));
+        }
+        if remaining == 0 {
+            return;
+        }
+        for mv in CubeMove::new_axis_iter(prev_axis, false) {
+            moves.push(mv);
+            extend(
+                moves,
+                prev_axis.update_with_new_move(mv, u8::MAX),
+                remaining - 1,
+                out,
+            );
+            moves.pop();
+        }
+    }
+
+    let mut out = Vec::new();
+    extend(&mut Vec::new(), CubePreviousAxis::None, max_len, &mut out);
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn alg(s: &str) -> Algorithm {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn find_commutators() {
+        let finder = CommutatorFinder::new(4);
+
+        let case = alg("[R U R', D]").cube();
+        let found = finder.find(case, 0);
+        assert!(
+            found
+                .iter()
+                .any(|c| c.a == alg("R U R'") && c.b == alg("D"))
+        );
+        // swapping the parts gives the inverse.
+        assert!(
+            finder
+                .find(case.inverse(), 0)
+                .iter()
+                .any(|c| c.a == alg("D") && c.b == alg("R U R'"))
+        );
+        for commutator in &found {
+            assert!(commutator.setup.is_empty());
+            assert_eq!(commutator.algorithm().cube(), case);
+            assert_eq!(
+                commutator.to_string().parse::<Algorithm>().unwrap().cube(),
+                case
+            );
+        }
+        assert!(
+            found
+                .windows(2)
+                .all(|w| w[0].algorithm().len() <= w[1].algorithm().len())
+        );
+
+        // the same cycle moved somewhere else.
+        let case = alg("[F: [R U R', D]]").cube();
+        let found = finder.find(case, 1);
+        assert!(
+            found
+                .iter()
+                .any(|c| c.setup == alg("F") && c.a == alg("R U R'"))
+        );
+        for commutator in &found {
+            assert!(commutator.setup.len() <= 1);
+            assert_eq!(commutator.algorithm().cube(), case);
+        }
+        assert_eq!(
+            Commutator {
+                setup: alg("F"),
+                a: alg("R U R'"),
+                b: alg("D")
+            }
+            .to_string(),
+            "[F: [R U R', D]]"
+        );
+
+        assert!(finder.find(alg("R").cube(), 2).is_empty());
+        assert!(finder.find(ReprCube::SOLVED, 2).is_empty());
+    }
+}
@@ -0,0 +1,304 @@
+use crate::{
+    cube_ops::{
+        cube_axis::Axis, cube_move::CubeMove, cube_prev_axis::CubePreviousAxis,
+        partial_reprs::edge_orient::EdgeOrient, repr_cube::ReprCube,
+    },
+    kociemba::coords::EdgeOrientRawCoord,
+    steps::pattern::{Piece, breadth_first, cube_pattern_index, pattern_database},
+};
+
+/// What an EO step solves besides the edge orientation.
+///
+/// The edges are named as they are for the FB axis. On the other axes they are the edges that
+/// `Axis::to_fb` moves onto those slots, e.g. UF and DF for RL.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum EoGoal {
+    /// only orient the edges.
+    Eo,
+    /// also solve DF and DB, as in ZZ.
+    Line,
+    /// also solve DF, DB and DL.
+    Arrow,
+    /// also solve every D edge.
+    Cross,
+}
+
+impl EoGoal {
+    pub const ALL: [EoGoal; 4] = [EoGoal::Eo, EoGoal::Line, EoGoal::Arrow, EoGoal::Cross];
+
+    /// the edges that have to be in their solved slot.
+    pub const fn edges(self) -> &'static [Piece] {
+        use Piece::Edge;
+        match self {
+            EoGoal::Eo => &[],
+            EoGoal::Line => &[Edge(4), Edge(5)],
+            EoGoal::Arrow => &[Edge(4), Edge(5), Edge(7)],
+            EoGoal::Cross => &[Edge(4), Edge(5), Edge(6), Edge(7)],
+        }
+    }
+
+    const fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// The edge orientation of a cube on one axis.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct EoCase {
+    pub axis: Axis,
+    /// edges that can't be solved without a quarter turn of the faces on `axis`.
+    pub bad_edges: u8,
+    /// the length of an optimal EO.
+    pub moves: u8,
+}
+
+impl std::fmt::Display for EoCase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "EO {}: {} bad edges, {} moves",
+            self.axis, self.bad_edges, self.moves
+        )
+    }
+}
+
+/// Pruning tables for the EO steps.
+///
+/// They are small enough to build on the spot: the exact EO distance of each of the 2048 edge
+/// orientations, and for each `EoGoal` the exact distance to solve its edges, ignoring the rest
+/// of the cube.
+pub struct EoTables {
+    orient: Box<[u8]>,
+    edges: [Box<[u8]>; 4],
+}
+
+impl Default for EoTables {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EoTables {
+    pub fn new() -> Self {
+        let orient = breadth_first(2048, EdgeOrient::SOLVED.into_coord().0 as usize, |coord| {
+            let edge_orient = EdgeOrient::from_coord(EdgeOrientRawCoord(coord as u16));
+            CubeMove::all_iter().map(move |mv| {
+                edge_orient
+                    .permute(mv.into_edge_perm())
+                    .correct(mv.into_edge_orient())
+                    .into_coord()
+                    .0 as usize
+            })
+        });
+
+        let edges = EoGoal::ALL.map(|goal| pattern_database(goal.edges()));
+
+        Self { orient, edges }
+    }
+
+    /// the edge orientation of `cube` on `axis`.
+    pub fn case(&self, cube: ReprCube, axis: Axis) -> EoCase {
+        let frame = cube.conjugate(axis.to_fb());
+        EoCase {
+            axis,
+            bad_edges: frame.edge_orient.0.iter().sum(),
+            moves: self.orient[frame.edge_orient.into_coord().0 as usize],
+        }
+    }
+
+    /// the edge orientation of `cube` on every axis.
+    pub fn cases(&self, cube: ReprCube) -> [EoCase; 3] {
+        Axis::ALL.map(|axis| self.case(cube, axis))
+    }
+
+    /// the length of an optimal solution to `goal` on `axis`.
+    pub fn distance(&self, cube: ReprCube, axis: Axis, goal: EoGoal) -> u8 {
+        if goal == EoGoal::Eo {
+            return self.case(cube, axis).moves;
+        }
+        self.solutions(cube, axis, goal)[0].len() as u8
+    }
+
+    /// every optimal solution to `goal` on `axis`, with the moves of each in canonical order.
+    pub fn solutions(&self, cube: ReprCube, axis: Axis, goal: EoGoal) -> Vec<Vec<CubeMove>> {
+        let search = Search {
+            tables: self,
+            axis,
+            goal,
+        };
+        let frame = cube.conjugate(axis.to_fb());
+        let mut solutions = Vec::new();
+        let mut len = search.heuristic(frame);
+
+        loop {
+            search.collect(
+                frame,
+                len,
+                CubePreviousAxis::None,
+                &mut Vec::new(),
+                &mut solutions,
+            );
+            if !solutions.is_empty() {
+                return solutions;
+            }
+            len += 1;
+        }
+    }
+}
+
+struct Search<'a> {
+    tables: &'a EoTables,
+    axis: Axis,
+    goal: EoGoal,
+}
+
+impl Search<'_> {
+    // `frame` is the cube conjugated so that `axis` is the FB axis.
+    fn heuristic(&self, frame: ReprCube) -> u8 {
+        let orient = self.tables.orient[frame.edge_orient.into_coord().0 as usize];
+        let edges =
+            self.tables.edges[self.goal.index()][cube_pattern_index(self.goal.edges(), frame)];
+        orient.max(edges)
+    }
+
+    fn collect(
+        &self,
+        frame: ReprCube,
+        remaining: u8,
+        prev_axis: CubePreviousAxis,
+        moves: &mut Vec<CubeMove>,
+        solutions: &mut Vec<Vec<CubeMove>>,
+    ) {
+        let heuristic = self.heuristic(frame);
+        if remaining == 0 {
+            if heuristic == 0 {
+                solutions.push(moves.clone());
+            }
+            return;
+        }
+        if heuristic > remaining {
+            return;
+        }
+
+        for mv in CubeMove::new_axis_iter(prev_axis, false) {
+            moves.push(mv);
+            self.collect(
+                frame.apply_move(mv.conjugate(self.axis.to_fb())),
+                remaining - 1,
+                prev_axis.update_with_new_move(mv, remaining),
+                moves,
+                solutions,
+            );
+            moves.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cube;
+
+    use super::*;
+
+    fn reaches(cube: ReprCube, axis: Axis, goal: EoGoal, moves: &[CubeMove]) -> bool {
+        let frame = moves
+            .iter()
+            .fold(cube, |cube, &mv| cube.apply_cube_move(mv))
+            .conjugate(axis.to_fb());
+        frame.edge_orient == EdgeOrient::SOLVED
+            && goal
+                .edges()
+                .iter()
+                .all(|&edge| edge.locate(frame) == edge.locate(ReprCube::SOLVED))
+    }
+
+    #[test]
+    fn eo_cases() {
+        let tables = EoTables::new();
+
+        // the hardest edge orientation takes 7 moves.
+        assert_eq!(tables.orient.iter().max(), Some(&7));
+        // every placement of the goal edges is reachable, with either orientation.
+        let reached = tables
+            .edges
+            .each_ref()
+            .map(|edges| edges.iter().filter(|&&d| d != u8::MAX).count());
+        assert_eq!(
+            reached,
+            [1, 12 * 11 * 4, 12 * 11 * 10 * 8, 12 * 11 * 10 * 9 * 16]
+        );
+
+        assert_eq!(
+            tables.cases(cube![F]),
+            [
+                EoCase {
+                    axis: Axis::UD,
+                    bad_edges: 0,
+                    moves: 0
+                },
+                EoCase {
+                    axis: Axis::FB,
+                    bad_edges: 4,
+                    moves: 1
+                },
+                EoCase {
+                    axis: Axis::RL,
+                    bad_edges: 0,
+                    moves: 0
+                },
+            ]
+        );
+        assert_eq!(tables.case(cube![U R], Axis::UD).bad_edges, 4);
+        assert_eq!(tables.case(cube![U R], Axis::RL).bad_edges, 4);
+        assert_eq!(tables.case(cube![U R], Axis::FB).bad_edges, 0);
+        assert_eq!(
+            tables.case(cube![F B], Axis::FB).to_string(),
+            "EO FB: 8 bad edges, 2 moves"
+        );
+    }
+
+    #[test]
+    fn optimal_solutions() {
+        let tables = EoTables::new();
+        let cube = cube![R U Rp Up F2 D L2 B Rp D2];
+
+        for axis in Axis::ALL {
+            let eo = tables.solutions(cube, axis, EoGoal::Eo);
+            assert!(
+                eo.iter()
+                    .all(|moves| moves.len() == tables.case(cube, axis).moves as usize)
+            );
+
+            let mut shortest = eo[0].len();
+            for goal in EoGoal::ALL {
+                let solutions = tables.solutions(cube, axis, goal);
+                let len = solutions[0].len();
+                assert!(len >= shortest);
+                shortest = len;
+
+                assert_eq!(tables.distance(cube, axis, goal) as usize, len);
+                for moves in &solutions {
+                    assert_eq!(moves.len(), len);
+                    assert!(
+                        reaches(cube, axis, goal, moves),
+                        "{axis} {goal:?} {moves:?}"
+                    );
+                }
+                let mut deduped = solutions.clone();
+                deduped
+                    .sort_by_key(|moves| moves.iter().map(|mv| mv.into_u8()).collect::<Vec<_>>());
+                deduped.dedup();
+                assert_eq!(deduped.len(), solutions.len());
+            }
+        }
+
+        assert_eq!(
+            tables.solutions(ReprCube::SOLVED, Axis::UD, EoGoal::Cross),
+            vec![vec![]]
+        );
+        assert_eq!(
+            tables.solutions(cube![F], Axis::FB, EoGoal::Line),
+            vec![vec![CubeMove::F3]]
+        );
+    }
+}
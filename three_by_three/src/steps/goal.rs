@@ -0,0 +1,291 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use crate::{
+    cube_ops::{cube_move::CubeMove, cube_prev_axis::CubePreviousAxis, repr_cube::ReprCube},
+    kociemba::search::capped_idastar::idastar_limited,
+    steps::pattern::{Piece, cube_pattern_index, pattern_database},
+};
+
+/// A set of pieces that have to reach their solved slot and orientation, with the rest of the cube
+/// ignored.
+///
+/// Bit `i` of `corners` and `edges` is the piece that belongs in slot `i`, in the slot order of
+/// `ReprCube`: corners UFR UFL UBR UBL DFR DFL DBR DBL, and edges UF UB UR UL DF DB DR DL FR FL BR
+/// BL.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct Goal {
+    pub corners: u8,
+    pub edges: u16,
+}
+
+impl Goal {
+    pub const CROSS: Goal = Goal {
+        corners: 0,
+        edges: 0b0000_1111_0000,
+    };
+    /// the F2L pairs of the FR, FL, BR and BL slots.
+    pub const F2L_PAIRS: [Goal; 4] = [
+        Goal {
+            corners: 1 << 4,
+            edges: 1 << 8,
+        },
+        Goal {
+            corners: 1 << 5,
+            edges: 1 << 9,
+        },
+        Goal {
+            corners: 1 << 6,
+            edges: 1 << 10,
+        },
+        Goal {
+            corners: 1 << 7,
+            edges: 1 << 11,
+        },
+    ];
+    /// the cross and the FR pair.
+    pub const XCROSS: Goal = Goal::CROSS.union(Goal::F2L_PAIRS[0]);
+    pub const F2L: Goal = Goal {
+        corners: 0b1111_0000,
+        edges: 0b1111_1111_0000,
+    };
+    /// the 2x2x2 block around DBL.
+    pub const BLOCK_2X2X2: Goal = Goal {
+        corners: 1 << 7,
+        edges: (1 << 5) | (1 << 7) | (1 << 11),
+    };
+    /// the 2x2x3 block on the D and B faces.
+    pub const BLOCK_2X2X3: Goal = Goal {
+        corners: (1 << 6) | (1 << 7),
+        edges: (1 << 5) | (1 << 6) | (1 << 7) | (1 << 10) | (1 << 11),
+    };
+    /// the 1x2x3 block on the L face.
+    pub const ROUX_FIRST_BLOCK: Goal = Goal {
+        corners: (1 << 5) | (1 << 7),
+        edges: (1 << 7) | (1 << 9) | (1 << 11),
+    };
+    /// the 1x2x3 block on the R face.
+    pub const ROUX_SECOND_BLOCK: Goal = Goal {
+        corners: (1 << 4) | (1 << 6),
+        edges: (1 << 6) | (1 << 8) | (1 << 10),
+    };
+
+    pub const fn union(self, other: Goal) -> Goal {
+        Goal {
+            corners: self.corners | other.corners,
+            edges: self.edges | other.edges,
+        }
+    }
+
+    /// the pieces of the goal, edges first.
+    pub fn pieces(self) -> Vec<Piece> {
+        let edges = (0..12)
+            .filter(|i| self.edges & (1 << i) != 0)
+            .map(Piece::Edge);
+        let corners = (0..8)
+            .filter(|i| self.corners & (1 << i) != 0)
+            .map(Piece::Corner);
+        edges.chain(corners).collect()
+    }
+
+    pub fn is_solved(self, cube: ReprCube) -> bool {
+        self.pieces().into_iter().all(|piece| {
+            let (Piece::Corner(slot) | Piece::Edge(slot)) = piece;
+            piece.locate(cube) == (slot, 0)
+        })
+    }
+}
+
+/// The most pieces in one pattern database, `24^4` entries.
+const PIECES_PER_DATABASE: usize = 4;
+
+/// The longest solution `GoalSolver` looks for, set by the path length of `idastar_limited`.
+pub const MAX_GOAL_LEN: u8 = 19;
+
+/// Finds optimal solutions to `Goal`s.
+///
+/// The pieces of a goal are split into groups of up to four, and the heuristic is the largest
+/// distance in the pattern databases of the groups. Each database is generated the first time a
+/// goal needs it and kept for later goals with the same group.
+#[derive(Default)]
+pub struct GoalSolver {
+    databases: Mutex<HashMap<Vec<Piece>, Arc<[u8]>>>,
+}
+
+impl GoalSolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// the length of an optimal solution of `goal`, or `None` if it takes more than `max_len`
+    /// moves.
+    pub fn distance(&self, cube: ReprCube, goal: Goal, max_len: u8) -> Option<u8> {
+        let heuristic = self.heuristic(goal);
+        // the search never ends a phase 1, so the remaining move count doesn't matter.
+        idastar_limited(
+            (cube, CubePreviousAxis::None),
+            |&(cube, prev_axis)| {
+                CubeMove::new_axis_iter(prev_axis, false)
+                    .into_iter()
+                    .map(move |mv| {
+                        let next = (cube.apply_move(mv), prev_axis.update_with_new_move(mv, 0));
+                        (next, 1)
+                    })
+            },
+            |&(cube, _)| heuristic.distance(cube),
+            |&(cube, _)| goal.is_solved(cube),
+            max_len.min(MAX_GOAL_LEN),
+        )
+        .map(|(_, len)| len)
+    }
+
+    /// every optimal solution of `goal` of at most `max_len` moves, with the moves of each in
+    /// canonical order. empty if the optimal solution is longer.
+    pub fn solve(&self, cube: ReprCube, goal: Goal, max_len: u8) -> Vec<Vec<CubeMove>> {
+        let Some(len) = self.distance(cube, goal, max_len) else {
+            return Vec::new();
+        };
+
+        let heuristic = self.heuristic(goal);
+        let mut solutions = Vec::new();
+        heuristic.collect(
+            cube,
+            len,
+            CubePreviousAxis::None,
+            &mut Vec::new(),
+            &mut solutions,
+        );
+        solutions
+    }
+
+    pub(crate) fn heuristic(&self, goal: Goal) -> Heuristic {
+        let mut databases = self.databases.lock().unwrap();
+        let groups = goal
+            .pieces()
+            .chunks(PIECES_PER_DATABASE)
+            .map(|pieces| {
+                let database = databases
+                    .entry(pieces.to_vec())
+                    .or_insert_with(|| pattern_database(pieces).into())
+                    .clone();
+                (pieces.to_vec(), database)
+            })
+            .collect();
+        Heuristic { groups }
+    }
+}
+
+pub(crate) struct Heuristic {
+    groups: Vec<(Vec<Piece>, Arc<[u8]>)>,
+}
+
+impl Heuristic {
+    pub(crate) fn distance(&self, cube: ReprCube) -> u8 {
+        self.groups
+            .iter()
+            .map(|(pieces, database)| database[cube_pattern_index(pieces, cube)])
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn collect(
+        &self,
+        cube: ReprCube,
+        remaining: u8,
+        prev_axis: CubePreviousAxis,
+        moves: &mut Vec<CubeMove>,
+        solutions: &mut Vec<Vec<CubeMove>>,
+    ) {
+        let distance = self.distance(cube);
+        if remaining == 0 {
+            if distance == 0 {
+                solutions.push(moves.clone());
+            }
+            return;
+        }
+        if distance > remaining {
+            return;
+        }
+
+        for mv in CubeMove::new_axis_iter(prev_axis, false) {
+            moves.push(mv);
+            self.collect(
+                cube.apply_move(mv),
+                remaining - 1,
+                prev_axis.update_with_new_move(mv, remaining),
+                moves,
+                solutions,
+            );
+            moves.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cube;
+
+    use super::*;
+
+    fn apply(cube: ReprCube, moves: &[CubeMove]) -> ReprCube {
+        moves.iter().fold(cube, |cube, &mv| cube.apply_move(mv))
+    }
+
+    #[test]
+    fn goals() {
+        assert_eq!(
+            Goal::F2L_PAIRS.into_iter().fold(Goal::CROSS, Goal::union),
+            Goal::F2L
+        );
+        assert_eq!(Goal::XCROSS.pieces().len(), 6);
+
+        assert!(Goal::F2L.is_solved(cube![U2]));
+        assert!(!Goal::F2L.is_solved(cube![R U Rp]));
+        assert!(!Goal::CROSS.is_solved(cube![R]));
+        assert!(Goal::ROUX_FIRST_BLOCK.is_solved(cube![R U R2]));
+        assert!(!Goal::ROUX_SECOND_BLOCK.is_solved(cube![R U R2]));
+        assert!(Goal::BLOCK_2X2X2.is_solved(cube![R U F]));
+        assert!(!Goal::BLOCK_2X2X3.is_solved(cube![R U F]));
+    }
+
+    #[test]
+    fn optimal_goal_solutions() {
+        let solver = GoalSolver::new();
+        let cube = cube![R U Rp Up F2 D L2 B Rp D2];
+
+        // every group of the cross fits in one database, so its heuristic is exact.
+        let cross = solver.solve(cube, Goal::CROSS, 10);
+        let len = solver.heuristic(Goal::CROSS).distance(cube);
+        assert_eq!(solver.distance(cube, Goal::CROSS, 10), Some(len));
+        assert!(!cross.is_empty());
+        for moves in &cross {
+            assert_eq!(moves.len(), len as usize);
+            assert!(Goal::CROSS.is_solved(apply(cube, moves)));
+        }
+
+        for goal in [Goal::XCROSS, Goal::BLOCK_2X2X2, Goal::ROUX_FIRST_BLOCK] {
+            let solutions = solver.solve(cube, goal, 12);
+            assert!(!solutions.is_empty(), "{goal:?}");
+            let len = solutions[0].len();
+            for moves in &solutions {
+                assert_eq!(moves.len(), len);
+                assert!(goal.is_solved(apply(cube, moves)));
+            }
+            let mut deduped = solutions.clone();
+            deduped.sort_by_key(|moves| moves.iter().map(|mv| mv.into_u8()).collect::<Vec<_>>());
+            deduped.dedup();
+            assert_eq!(deduped.len(), solutions.len());
+
+            assert!(solver.solve(cube, goal, len as u8 - 1).is_empty());
+        }
+
+        // the scramble leaves the pair joined, so it goes back in without undoing the first R.
+        use CubeMove::*;
+        assert_eq!(
+            solver.solve(cube![R U Rp], Goal::F2L_PAIRS[0], 5),
+            vec![vec![U3, R3]]
+        );
+    }
+}
@@ -0,0 +1,182 @@
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+};
+
+use crate::cube_ops::{
+    algorithm::Algorithm, cube_move::CubeMove, cube_sym::CubeSymmetry, repr_cube::ReprCube,
+};
+
+/// Insertions a fewest moves solver tries first: corner 3-cycles, edge 3-cycles and a pair of
+/// twisted corners. `InsertionFinder` also tries each of them in every orientation, mirrored and
+/// inverted.
+pub const INSERTION_LIBRARY: [&str; 6] = [
+    "[R U R', D]",
+    "[R U R', D2]",
+    "[R' D R, U2]",
+    "[M', U2]",
+    "R2 U R U R' U' R' U' R' U R'",
+    "[R' D' R D R' D' R D, U]",
+];
+
+/// An algorithm inserted into a skeleton.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Insertion {
+    /// the number of skeleton moves before the insertion.
+    pub position: usize,
+    pub inserted: Algorithm,
+    /// the whole solution, with the moves that cancel removed.
+    pub solution: Algorithm,
+    /// how many moves cancelled out of the skeleton and the insertion.
+    pub cancelled: usize,
+}
+
+/// Finds where an algorithm from a library can be inserted into a skeleton to solve the cube.
+pub struct InsertionFinder {
+    // every conjugate of every library algorithm and of its inverse, by the cube it makes.
+    algorithms: HashMap<ReprCube, Vec<Algorithm>>,
+}
+
+impl Default for InsertionFinder {
+    /// a finder with `INSERTION_LIBRARY`.
+    fn default() -> Self {
+        let library = INSERTION_LIBRARY.map(|s| s.parse().unwrap());
+        Self::new(&library)
+    }
+}
+
+impl InsertionFinder {
+    pub fn new(library: &[Algorithm]) -> Self {
+        let mut algorithms: HashMap<_, Vec<Algorithm>> = HashMap::new();
+        let variants = library
+            .iter()
+            .flat_map(|algorithm| [algorithm.clone(), algorithm.inverse()]);
+        for algorithm in variants {
+            for sym in CubeSymmetry::all_iter() {
+                let conjugated = algorithm.conjugate(sym);
+                let entry = algorithms.entry(conjugated.cube()).or_default();
+                if !entry.contains(&conjugated) {
+                    entry.push(conjugated);
+                }
+            }
+        }
+        Self { algorithms }
+    }
+
+    /// every insertion of one library algorithm into `skeleton` that solves `scramble`, the most
+    /// cancelled moves first, then the shortest solutions.
+    pub fn find(&self, scramble: ReprCube, skeleton: &[CubeMove]) -> Vec<Insertion> {
+        let mut insertions = Vec::new();
+        let mut seen = HashSet::new();
+
+        // the cubes before and after each position.
+        let mut before = scramble;
+        let mut after = Algorithm::new(skeleton.to_vec()).cube();
+
+        for position in 0..=skeleton.len() {
+            // `before`, then the insertion, then `after` is solved.
+            let needed = before.inverse().then(after.inverse());
+            for inserted in self.algorithms.get(&needed).into_iter().flatten() {
+                let moves = skeleton[..position]
+                    .iter()
+                    .chain(&inserted.moves)
+                    .chain(&skeleton[position..])
+                    .copied()
+                    .collect();
+                let solution = Algorithm::new(moves).simplify();
+                debug_assert_eq!(scramble.then(solution.cube()), ReprCube::SOLVED);
+
+                if seen.insert(solution.canonicalize()) {
+                    insertions.push(Insertion {
+                        position,
+                        inserted: inserted.clone(),
+                        cancelled: skeleton.len() + inserted.len() - solution.len(),
+                        solution,
+                    });
+                }
+            }
+
+            if let Some(&mv) = skeleton.get(position) {
+                let mv_cube = ReprCube::SOLVED.apply_move(mv);
+                before = before.apply_move(mv);
+                after = mv_cube.inverse().then(after);
+            }
+        }
+
+        insertions.sort_by_key(|insertion| {
+            (
+                Reverse(insertion.cancelled),
+                insertion.solution.len(),
+                insertion.position,
+            )
+        });
+        insertions
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn alg(s: &str) -> Algorithm {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn library_is_three_cycles_and_twists() {
+        for entry in INSERTION_LIBRARY {
+            let cycles = alg(entry).cube().cycle_structure();
+            let all: Vec<_> = cycles.corners.iter().chain(&cycles.edges).collect();
+            let three_cycle = all.len() == 1 && all[0].slots.len() == 3;
+            let twist = cycles.edges.is_empty()
+                && !cycles.corners.is_empty()
+                && cycles.corners.iter().all(|cycle| cycle.slots.len() == 1);
+            assert!(three_cycle || twist, "{entry}: {cycles}");
+        }
+    }
+
+    #[test]
+    fn find_insertions() {
+        let finder = InsertionFinder::default();
+
+        // a skeleton that leaves a corner 3-cycle, which an insertion after `R U' F` solves.
+        let skeleton = alg("R U' F D2 L B' R2 U");
+        let inserted = alg("[R U R', D]").conjugate(CubeSymmetry(5));
+        let mut full = skeleton.moves[..3].to_vec();
+        full.extend(&inserted.moves);
+        full.extend(&skeleton.moves[3..]);
+        let scramble = Algorithm::new(full).cube().inverse();
+
+        let insertions = finder.find(scramble, &skeleton.moves);
+        assert!(insertions.iter().any(|insertion| insertion.position == 3));
+        for insertion in &insertions {
+            assert_eq!(scramble.then(insertion.solution.cube()), ReprCube::SOLVED);
+            assert_eq!(
+                insertion.solution.len() + insertion.cancelled,
+                skeleton.len() + insertion.inserted.len()
+            );
+        }
+        assert!(
+            insertions
+                .windows(2)
+                .all(|w| w[0].cancelled >= w[1].cancelled)
+        );
+
+        // an insertion at the end cancels with the last move of the skeleton.
+        let skeleton = alg("F2 L D'");
+        let scramble = alg("F2 L D' [D, R U R']").cube().inverse();
+        let insertions = finder.find(scramble, &skeleton.moves);
+        assert!(insertions.iter().any(|insertion| insertion.position == 3
+            && insertion.cancelled == 2
+            && insertion.solution == alg("F2 L R U R' D' R U' R'")));
+        assert!(insertions[0].cancelled >= 2);
+
+        // nothing to insert into a solution.
+        let solution = alg("R U F");
+        assert!(
+            finder
+                .find(solution.inverse().cube(), &solution.moves)
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,429 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+
+use crate::{
+    cube_ops::{
+        cube_move::CubeMove,
+        notation::parse_moves,
+        partial_reprs::{corner_perm::CornerPerm, edge_perm::EdgePerm},
+        repr_cube::{InvalidCube, ReprCube},
+    },
+    steps::goal::Goal,
+};
+
+/// OLL cases by number, each with an algorithm that solves it.
+const OLL_ALGORITHMS: [(&str, &str); 57] = [
+    ("1", "R U2 R2 F R F' U2 R' F R F'"),
+    ("2", "r U r' U2 r U2 R' U2 R U' r'"),
+    ("3", "f R U R' U' f' U' F R U R' U' F'"),
+    ("4", "f R U R' U' f' U F R U R' U' F'"),
+    ("5", "r' U2 R U R' U r"),
+    ("6", "r U2 R' U' R U' r'"),
+    ("7", "r U R' U R U2 r'"),
+    ("8", "r' U' R U' R' U2 r"),
+    ("9", "R U R' U' R' F R2 U R' U' F'"),
+    ("10", "R U R' U R' F R F' R U2 R'"),
+    ("11", "r U R' U R' F R F' R U2 r'"),
+    ("12", "M' R' U' R U' R' U2 R U' M"),
+    ("13", "F U R U' R2 F' R U R U' R'"),
+    ("14", "R' F R U R' F' R F U' F'"),
+    ("15", "r' U' r R' U' R U r' U r"),
+    ("16", "r U r' R U R' U' r U' r'"),
+    ("17", "R U R' U R' F R F' U2 R' F R F'"),
+    ("18", "r U R' U R U2 r2 U' R U' R' U2 r"),
+    ("19", "M U R U R' U' M' R' F R F'"),
+    ("20", "r U R' U' M2 U R U' R' U' M'"),
+    ("21", "R U2 R' U' R U R' U' R U' R'"),
+    ("22", "R U2 R2 U' R2 U' R2 U2 R"),
+    ("23", "R2 D' R U2 R' D R U2 R"),
+    ("24", "r U R' U' r' F R F'"),
+    ("25", "F' r U R' U' r' F R"),
+    ("26", "R U2 R' U' R U' R'"),
+    ("27", "R U R' U R U2 R'"),
+    ("28", "r U R' U' M U R U' R'"),
+    ("29", "R U R' U' R U' R' F' U' F R U R'"),
+    ("30", "F R' F R2 U' R' U' R U R' F2"),
+    ("31", "R' U' F U R U' R' F' R"),
+    ("32", "L U F' U' L' U L F L'"),
+    ("33", "R U R' U' R' F R F'"),
+    ("34", "R U R2 U' R' F R U R U' F'"),
+    ("35", "R U2 R2 F R F' R U2 R'"),
+    ("36", "L' U' L U' L' U L U L F' L' F"),
+    ("37", "F R' F' R U R U' R'"),
+    ("38", "R U R' U R U' R' U' R' F R F'"),
+    ("39", "L F' L' U' L U F U' L'"),
+    ("40", "R' F R U R' U' F' U R"),
+    ("41", "R U R' U R U2 R' F R U R' U' F'"),
+    ("42", "R' U' R U' R' U2 R F R U R' U' F'"),
+    ("43", "F' U' L' U L F"),
+    ("44", "F U R U' R' F'"),
+    ("45", "F R U R' U' F'"),
+    ("46", "R' U' R' F R F' U R"),
+    ("47", "R' U' R' F R F' R' F R F' U R"),
+    ("48", "F R U R' U' R U R' U' F'"),
+    ("49", "r U' r2 U r2 U r2 U' r"),
+    ("50", "r' U r2 U' r2 U' r2 U r'"),
+    ("51", "F U R U' R' U R U' R' F'"),
+    ("52", "R U R' U R U' B U' B' R'"),
+    ("53", "r' U2 R U R' U' R U R' U r"),
+    ("54", "r U2 R' U' R U R' U' R U' r'"),
+    ("55", "R' F R U R U' R2 F' R2 U' R' U R U R'"),
+    ("56", "r' U' r U' R' U R U' R' U R r' U r"),
+    ("57", "R U R' U' M' U R U' r'"),
+];
+
+/// PLL cases by name, each with an algorithm that solves it.
+const PLL_ALGORITHMS: [(&str, &str); 21] = [
+    ("Aa", "x R' U R' D2 R U' R' D2 R2 x'"),
+    ("Ab", "x R2 D2 R U R' D2 R U' R x'"),
+    ("E", "x' R U' R' D R U R' D' R U R' D R U' R' D' x"),
+    ("F", "R' U' F' R U R' U' R' F R2 U' R' U' R U R' U R"),
+    ("Ga", "R2 U R' U R' U' R U' R2 U' D R' U R D'"),
+    ("Gb", "R' U' R U D' R2 U R' U R U' R U' R2 D"),
+    ("Gc", "R2 U' R U' R U R' U R2 U D' R U' R' D"),
+    ("Gd", "R U R' U' D R2 U' R U' R' U R' U R2 D'"),
+    ("H", "M2 U M2 U2 M2 U M2"),
+    ("Ja", "x R2 F R F' R U2 r' U r U2 x'"),
+    ("Jb", "R U R' F' R U R' U' R' F R2 U' R'"),
+    (
+        "Na",
+        "R U R' U R U R' F' R U R' U' R' F R2 U' R' U2 R U' R'",
+    ),
+    ("Nb", "R' U R U' R' F' U' F R U R' F R' F' R U' R"),
+    ("Ra", "R U' R' U' R U R D R' U' R D' R' U2 R'"),
+    ("Rb", "R2 F R U R U' R' F' R U2 R' U2 R"),
+    ("T", "R U R' U' R' F R2 U' R' U' R U R' F'"),
+    ("Ua", "M2 U M U2 M' U M2"),
+    ("Ub", "M2 U' M U2 M' U' M2"),
+    ("V", "R' U R' U' y R' F' R2 U' R' U R' F R F"),
+    ("Y", "F R U' R' U' R U R' F' R U R' U' R' F R F'"),
+    ("Z", "M' U M2 U M2 U M' U2 M2"),
+];
+
+/// The names of the corner orientations with oriented edges, by OLL number.
+const OCLL_NAMES: [(&str, &str); 7] = [
+    ("21", "H"),
+    ("22", "Pi"),
+    ("23", "U"),
+    ("24", "T"),
+    ("25", "L"),
+    ("26", "AS"),
+    ("27", "S"),
+];
+
+/// A set of last layer cases, each solved by one algorithm.
+///
+/// COLL and ZBLL are not supported, as there is no table of their named cases and algorithms to
+/// recognize them by.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum LlSet {
+    /// orient the last layer.
+    Oll,
+    /// permute an oriented last layer.
+    Pll,
+}
+
+impl std::fmt::Display for LlSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            LlSet::Oll => "OLL",
+            LlSet::Pll => "PLL",
+        })
+    }
+}
+
+/// A recognized last layer case.
+///
+/// The cube is solved, or has the step done, by `pre_auf`, then the algorithm of the case, then
+/// `post_auf`. A y rotation of a cube with F2L solved moves the last layer like a U turn, so the
+/// AUFs also cover recognizing from another side.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct LlCase {
+    pub set: LlSet,
+    /// the OLL number or PLL name, or "skip" when there is nothing to do but AUF.
+    pub name: String,
+    pub pre_auf: Option<CubeMove>,
+    pub post_auf: Option<CubeMove>,
+}
+
+impl std::fmt::Display for LlCase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.set, self.name)
+    }
+}
+
+/// Why a cube has no last layer case.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum RecognizeError {
+    InvalidCube(InvalidCube),
+    F2lUnsolved,
+    /// PLL needs an oriented last layer.
+    LastLayerUnoriented,
+}
+
+impl std::fmt::Display for RecognizeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RecognizeError::InvalidCube(invalid) => write!(f, "invalid cube: {invalid}"),
+            RecognizeError::F2lUnsolved => f.write_str("F2L is not solved"),
+            RecognizeError::LastLayerUnoriented => f.write_str("the last layer is not oriented"),
+        }
+    }
+}
+
+impl std::error::Error for RecognizeError {}
+
+impl From<InvalidCube> for RecognizeError {
+    fn from(invalid: InvalidCube) -> Self {
+        RecognizeError::InvalidCube(invalid)
+    }
+}
+
+/// Recognizes OLL and PLL cases, by the algorithms above.
+pub struct LlRecognizer {
+    oll: CaseTable,
+    pll: CaseTable,
+}
+
+impl Default for LlRecognizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+struct CaseTable {
+    set: LlSet,
+    // the name of each case, and the cube its algorithm solves, as seen by `set.project`.
+    cases: Vec<(String, ReprCube)>,
+    by_key: HashMap<ReprCube, usize>,
+}
+
+impl CaseTable {
+    fn new(set: LlSet, cases: Vec<(String, ReprCube)>) -> Self {
+        let cases: Vec<_> = cases
+            .into_iter()
+            .map(|(name, cube)| (name, set.project(cube)))
+            .collect();
+        let by_key = cases
+            .iter()
+            .enumerate()
+            .map(|(i, &(_, cube))| (set.key(cube), i))
+            .collect();
+        Self { set, cases, by_key }
+    }
+
+    fn from_algorithms(set: LlSet, algorithms: &[(&str, &str)]) -> Self {
+        let skip = ("skip".to_string(), ReprCube::SOLVED);
+        let cases = algorithms.iter().map(|&(name, algorithm)| {
+            let moves = parse_moves(algorithm).unwrap();
+            (name.to_string(), inverse(&moves))
+        });
+        Self::new(set, std::iter::once(skip).chain(cases).collect())
+    }
+
+    fn recognize(&self, cube: ReprCube) -> LlCase {
+        let (name, reference) = &self.cases[self.by_key[&self.set.key(cube)]];
+        let (post, pre) = (0..4)
+            .cartesian_product(0..4)
+            .find(|&(post, pre)| {
+                self.set.project(auf(post).then(cube).then(auf(pre))) == *reference
+            })
+            .unwrap();
+        LlCase {
+            set: self.set,
+            name: name.clone(),
+            pre_auf: auf_move(pre),
+            post_auf: auf_move(post),
+        }
+    }
+}
+
+impl LlSet {
+    // the part of the cube the cases of the set tell apart.
+    fn project(self, cube: ReprCube) -> ReprCube {
+        match self {
+            LlSet::Oll => ReprCube {
+                corner_perm: CornerPerm::SOLVED,
+                edge_perm: EdgePerm::SOLVED,
+                ..cube
+            },
+            LlSet::Pll => cube,
+        }
+    }
+
+    // the same for every cube in a case.
+    fn key(self, cube: ReprCube) -> ReprCube {
+        (0..4)
+            .cartesian_product(0..4)
+            .map(|(post, pre)| self.project(auf(post).then(cube).then(auf(pre))))
+            .min()
+            .unwrap()
+    }
+}
+
+impl LlRecognizer {
+    pub fn new() -> Self {
+        Self {
+            oll: CaseTable::from_algorithms(LlSet::Oll, &OLL_ALGORITHMS),
+            pll: CaseTable::from_algorithms(LlSet::Pll, &PLL_ALGORITHMS),
+        }
+    }
+
+    /// the case of `cube` in `set`.
+    pub fn recognize(&self, cube: ReprCube, set: LlSet) -> Result<LlCase, RecognizeError> {
+        cube.validate()?;
+        if !Goal::F2L.is_solved(cube) {
+            return Err(RecognizeError::F2lUnsolved);
+        }
+
+        let edges_oriented = cube.edge_orient.0.iter().all(|&orient| orient == 0);
+        match set {
+            LlSet::Oll => Ok(self.oll.recognize(cube)),
+            LlSet::Pll if !edges_oriented || !corners_oriented(cube) => {
+                Err(RecognizeError::LastLayerUnoriented)
+            }
+            LlSet::Pll => Ok(self.pll.recognize(cube)),
+        }
+    }
+
+    /// the name of the corner orientation of `cube`, which has F2L solved and the edges oriented:
+    /// H, Pi, U, T, L, AS, S, or O for oriented corners.
+    pub(crate) fn corner_orientation(&self, cube: ReprCube) -> &'static str {
+        let oll = self.oll.recognize(cube).name;
+        OCLL_NAMES
+            .iter()
+            .find(|&&(number, _)| number == oll)
+            .map_or("O", |&(_, name)| name)
+    }
+
+    /// the names of the cases of `set`, "skip" first.
+    pub fn case_names(&self, set: LlSet) -> impl Iterator<Item = &str> {
+        let table = match set {
+            LlSet::Oll => &self.oll,
+            LlSet::Pll => &self.pll,
+        };
+        table.cases.iter().map(|(name, _)| name.as_str())
+    }
+}
+
+/// the cube that `moves` solve.
+pub(crate) fn inverse(moves: &[CubeMove]) -> ReprCube {
+    moves.iter().rev().fold(ReprCube::SOLVED, |cube, &mv| {
+        cube.apply_move(mv).apply_move(mv).apply_move(mv)
+    })
+}
+
+/// `quarter_turns` U turns.
+pub(crate) fn auf(quarter_turns: u8) -> ReprCube {
+    (0..quarter_turns).fold(ReprCube::SOLVED, |cube, _| cube.apply_move(CubeMove::U1))
+}
+
+/// the U turn of `quarter_turns` quarter turns, `None` for 0.
+pub(crate) fn auf_move(quarter_turns: u8) -> Option<CubeMove> {
+    [
+        None,
+        Some(CubeMove::U1),
+        Some(CubeMove::U2),
+        Some(CubeMove::U3),
+    ][quarter_turns as usize]
+}
+
+fn corners_oriented(cube: ReprCube) -> bool {
+    cube.corner_orient.0.iter().all(|&orient| orient == 0)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cube;
+
+    use super::*;
+
+    fn apply(cube: ReprCube, moves: &[CubeMove]) -> ReprCube {
+        moves.iter().fold(cube, |cube, &mv| cube.apply_move(mv))
+    }
+
+    #[test]
+    fn case_tables() {
+        let recognizer = LlRecognizer::new();
+
+        for (set, algorithms) in [
+            (LlSet::Oll, &OLL_ALGORITHMS[..]),
+            (LlSet::Pll, &PLL_ALGORITHMS[..]),
+        ] {
+            let table = match set {
+                LlSet::Oll => &recognizer.oll,
+                _ => &recognizer.pll,
+            };
+            // no two algorithms solve the same case.
+            assert_eq!(table.by_key.len(), algorithms.len() + 1, "{set}");
+
+            for &(name, algorithm) in algorithms {
+                let case = inverse(&parse_moves(algorithm).unwrap());
+                assert!(Goal::F2L.is_solved(case), "{set} {name}");
+                if set == LlSet::Pll {
+                    assert!(corners_oriented(case) && case.edge_orient.0.iter().all(|&o| o == 0));
+                }
+                assert_eq!(recognizer.recognize(case, set).unwrap().name, name);
+            }
+        }
+
+        // the seven corner orientations with oriented edges, and oriented corners.
+        for &(number, name) in &OCLL_NAMES {
+            let algorithm = OLL_ALGORITHMS[number.parse::<usize>().unwrap() - 1].1;
+            let case = inverse(&parse_moves(algorithm).unwrap());
+            assert_eq!(recognizer.corner_orientation(case), name);
+        }
+        let t_perm = inverse(&parse_moves(PLL_ALGORITHMS[15].1).unwrap());
+        assert_eq!(recognizer.corner_orientation(t_perm), "O");
+    }
+
+    #[test]
+    fn recognize_with_auf() {
+        let recognizer = LlRecognizer::new();
+
+        // sune, with the last layer turned before and after.
+        let sune = parse_moves("R U R' U R U2 R'").unwrap();
+        let cube = cube![U2].then(inverse(&sune)).then(cube![Up]);
+        let case = recognizer.recognize(cube, LlSet::Oll).unwrap();
+        assert_eq!(case.to_string(), "OLL 27");
+        let pre = case.pre_auf.map_or(vec![], |mv| vec![mv]);
+        let oriented = apply(apply(cube, &pre), &sune);
+        assert!(corners_oriented(oriented));
+
+        // every step of the case solves the cube.
+        for (set, name, algorithm) in [
+            (LlSet::Pll, "T", PLL_ALGORITHMS[15].1),
+            (LlSet::Pll, "Gc", PLL_ALGORITHMS[6].1),
+            (LlSet::Pll, "Ua", PLL_ALGORITHMS[16].1),
+        ] {
+            let moves = parse_moves(algorithm).unwrap();
+            for (post, pre) in (0..4).cartesian_product(0..4) {
+                let cube = auf(post).then(inverse(&moves)).then(auf(pre));
+                let case = recognizer.recognize(cube, set).unwrap();
+                assert_eq!(case.name, name);
+                let solution: Vec<_> = case
+                    .pre_auf
+                    .into_iter()
+                    .chain(moves.iter().copied())
+                    .chain(case.post_auf)
+                    .collect();
+                assert_eq!(apply(cube, &solution), ReprCube::SOLVED);
+            }
+        }
+
+        assert_eq!(
+            recognizer.recognize(cube![U2], LlSet::Pll).unwrap().name,
+            "skip"
+        );
+
+        assert_eq!(
+            recognizer.recognize(cube![R U], LlSet::Oll),
+            Err(RecognizeError::F2lUnsolved)
+        );
+        assert_eq!(
+            recognizer.recognize(cube![F R U Rp Up Fp], LlSet::Pll),
+            Err(RecognizeError::LastLayerUnoriented)
+        );
+    }
+}
@@ -0,0 +1,10 @@
+pub mod algorithm_generator;
+pub mod bld;
+pub mod commutator;
+pub mod eo;
+pub mod goal;
+pub mod insertion;
+pub mod last_layer;
+pub mod niss;
+pub mod pattern;
+pub mod reconstruction;
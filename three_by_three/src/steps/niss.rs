@@ -0,0 +1,258 @@
+use std::str::FromStr;
+
+use anyhow::bail;
+
+use crate::{
+    cube_ops::{algorithm::Algorithm, cube_axis::Axis, repr_cube::ReprCube},
+    kociemba::{
+        search::{DominoReductionOptions, domino_reductions},
+        tables::Tables,
+    },
+    steps::eo::{EoGoal, EoTables},
+};
+
+/// Which cube a step is searched on, the scrambled cube or its inverse.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum SearchDirection {
+    /// the scrambled cube itself.
+    #[default]
+    Normal,
+    /// the inverse of the scrambled cube. moves found on it solve the scrambled cube once inverted.
+    Inverse,
+}
+
+/// A partial solution found by switching between the scramble and its inverse.
+///
+/// `normal` was found on the scramble and `inverse` on the inverse scramble. the inverse moves go
+/// at the end of the final solution, inverted, so whatever is found next goes between the two.
+/// written in the usual notation with the inverse moves in parentheses, e.g. `R U (F' D)`.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct NissSolution {
+    pub normal: Algorithm,
+    pub inverse: Algorithm,
+}
+
+impl NissSolution {
+    /// the cube left to solve from the normal side: `inverse` inverted, then the scramble, then
+    /// `normal`.
+    pub fn remaining(&self, scramble: ReprCube) -> ReprCube {
+        self.inverse
+            .cube()
+            .inverse()
+            .then(scramble)
+            .then(self.normal.cube())
+    }
+
+    /// the cube left to solve from the inverse side, the inverse of `remaining`.
+    pub fn remaining_inverse(&self, scramble: ReprCube) -> ReprCube {
+        self.remaining(scramble).inverse()
+    }
+
+    /// the partial solution with `moves` found on the side of `direction`.
+    pub fn then(&self, direction: SearchDirection, moves: &Algorithm) -> Self {
+        let mut next = self.clone();
+        let part = match direction {
+            SearchDirection::Normal => &mut next.normal,
+            SearchDirection::Inverse => &mut next.inverse,
+        };
+        part.moves.extend(&moves.moves);
+        next
+    }
+
+    /// the moves that solve the scramble, when `rest` solves `remaining`.
+    pub fn join(&self, rest: &Algorithm) -> Algorithm {
+        let moves = self
+            .normal
+            .moves
+            .iter()
+            .chain(&rest.moves)
+            .chain(&self.inverse.inverse().moves)
+            .copied()
+            .collect();
+        Algorithm::new(moves).simplify()
+    }
+
+    /// every way to continue with an optimal solution to `goal` on `axis`, on either side.
+    ///
+    /// a cube has EO exactly when its inverse has, so later steps can switch sides freely.
+    pub fn eo_continuations(
+        &self,
+        scramble: ReprCube,
+        tables: &EoTables,
+        axis: Axis,
+        goal: EoGoal,
+    ) -> Vec<Self> {
+        self.continuations(scramble, |cube| {
+            tables
+                .solutions(cube, axis, goal)
+                .into_iter()
+                .map(Algorithm::new)
+                .collect()
+        })
+    }
+
+    /// every way to continue with a domino reduction from `options`, on either side.
+    pub fn domino_continuations(
+        &self,
+        scramble: ReprCube,
+        tables: &Tables,
+        options: &DominoReductionOptions,
+    ) -> Vec<Self> {
+        self.continuations(scramble, |cube| {
+            domino_reductions(cube, tables, options)
+                .into_iter()
+                .map(|reduction| Algorithm::new(reduction.moves))
+                .collect()
+        })
+    }
+
+    // the continuations from `solve` on the normal side, then on the inverse side.
+    fn continuations(
+        &self,
+        scramble: ReprCube,
+        solve: impl Fn(ReprCube) -> Vec<Algorithm>,
+    ) -> Vec<Self> {
+        let sides = [
+            (SearchDirection::Normal, self.remaining(scramble)),
+            (SearchDirection::Inverse, self.remaining_inverse(scramble)),
+        ];
+        sides
+            .into_iter()
+            .flat_map(|(direction, cube)| {
+                solve(cube)
+                    .into_iter()
+                    .map(move |moves| self.then(direction, &moves))
+            })
+            .collect()
+    }
+}
+
+impl FromStr for NissSolution {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let mut normal = String::new();
+        let mut inverse = String::new();
+        let mut in_inverse = false;
+
+        for c in s.chars() {
+            match (c, in_inverse) {
+                ('(', false) => in_inverse = true,
+                (')', true) => in_inverse = false,
+                ('(' | ')', _) => bail!("unbalanced parentheses in {s:?}"),
+                (_, false) => normal.push(c),
+                (_, true) => inverse.push(c),
+            }
+            // keep moves on either side of a parenthesis apart.
+            if c == '(' || c == ')' {
+                normal.push(' ');
+                inverse.push(' ');
+            }
+        }
+        if in_inverse {
+            bail!("unbalanced parentheses in {s:?}");
+        }
+
+        Ok(Self {
+            normal: normal.parse()?,
+            inverse: inverse.parse()?,
+        })
+    }
+}
+
+impl std::fmt::Display for NissSolution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.normal)?;
+        if !self.inverse.is_empty() {
+            if !self.normal.is_empty() {
+                f.write_str(" ")?;
+            }
+            write!(f, "({})", self.inverse)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn alg(s: &str) -> Algorithm {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn join_normal_and_inverse() -> anyhow::Result<()> {
+        let niss: NissSolution = "R U (F' D) L2".parse()?;
+        assert_eq!(niss.normal, alg("R U L2"));
+        assert_eq!(niss.inverse, alg("F' D"));
+        assert_eq!(niss.to_string(), "R U L2 (F' D)");
+        assert_eq!("(B)".parse::<NissSolution>()?.to_string(), "(B)");
+        assert!("R (U".parse::<NissSolution>().is_err());
+        assert!("R U)".parse::<NissSolution>().is_err());
+
+        // a scramble that `normal`, then `middle`, then `inverse` inverted solves.
+        let middle = alg("B2 D' F");
+        let solution = alg("R U L2 B2 D' F D' F");
+        let scramble = solution.cube().inverse();
+
+        assert_eq!(niss.remaining(scramble), middle.cube().inverse());
+        assert_eq!(niss.remaining_inverse(scramble), middle.cube());
+        assert_eq!(niss.join(&middle), solution);
+        assert_eq!(scramble.then(niss.join(&middle).cube()), ReprCube::SOLVED);
+
+        // moves found on either side end up in the same place.
+        let normal = niss.then(SearchDirection::Normal, &alg("B2"));
+        assert_eq!(normal.join(&alg("D' F")), solution);
+        let inverse = niss.then(SearchDirection::Inverse, &alg("F'"));
+        assert_eq!(inverse.join(&alg("B2 D'")), solution);
+
+        Ok(())
+    }
+
+    #[test]
+    fn eo_and_domino_continuations() -> anyhow::Result<()> {
+        let scramble = alg("R' U' F D2 L2 F R2 U2 R2 B D2 L F' U' L2 D R' F2").cube();
+        let niss: NissSolution = "F' (U)".parse()?;
+        let eo_tables = EoTables::new();
+
+        let eos = niss.eo_continuations(scramble, &eo_tables, Axis::FB, EoGoal::Eo);
+        assert!(eos.iter().any(|eo| eo.normal.len() > niss.normal.len()));
+        assert!(eos.iter().any(|eo| eo.inverse.len() > niss.inverse.len()));
+        for eo in &eos {
+            // EO holds on both sides, whichever side it was found on.
+            assert_eq!(
+                eo_tables.case(eo.remaining(scramble), Axis::FB).bad_edges,
+                0
+            );
+            assert_eq!(
+                eo_tables
+                    .case(eo.remaining_inverse(scramble), Axis::FB)
+                    .bad_edges,
+                0
+            );
+        }
+
+        let tables = Tables::new("tables")?;
+        let options = DominoReductionOptions {
+            axis: Some(Axis::UD),
+            max_len: 12,
+            preserve_eo: Some(Axis::FB),
+            max_results: 20,
+            ..Default::default()
+        };
+        let eo = &eos[0];
+        let drs = eo.domino_continuations(scramble, &tables, &options);
+        assert!(!drs.is_empty());
+        for dr in &drs {
+            assert!(tables.is_domino_reduced(dr.remaining(scramble)));
+            assert!(tables.is_domino_reduced(dr.remaining_inverse(scramble)));
+            assert_eq!(
+                eo_tables.case(dr.remaining(scramble), Axis::FB).bad_edges,
+                0
+            );
+        }
+
+        Ok(())
+    }
+}
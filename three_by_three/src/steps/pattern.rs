@@ -0,0 +1,201 @@
+use std::collections::VecDeque;
+
+use crate::cube_ops::{
+    cube_move::CubeMove,
+    partial_reprs::{corner_perm::CornerPerm, edge_perm::EdgePerm},
+    repr_cube::ReprCube,
+};
+
+/// One piece of the cube, by its solved slot.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Piece {
+    Corner(u8),
+    Edge(u8),
+}
+
+// `SLOT_AFTER[mv][slot]` is where `mv` moves the piece in `slot`. corner slots come first, then
+// edge slots.
+const SLOT_AFTER: [[u8; 20]; 18] = const {
+    let mut table = [[0; 20]; 18];
+    let mut i = 0;
+    while i < 18 {
+        let mv: CubeMove = unsafe { core::mem::transmute(i as u8) };
+        let corners = CornerPerm::SOLVED.then(mv.into_corner_perm()).0.0;
+        let edges = EdgePerm::SOLVED.then(mv.into_edge_perm()).0.0;
+        let mut slot = 0;
+        while slot < 8 {
+            table[i][corners[slot] as usize] = slot as u8;
+            slot += 1;
+        }
+        let mut slot = 0;
+        while slot < 12 {
+            table[i][8 + edges[slot] as usize] = slot as u8;
+            slot += 1;
+        }
+        i += 1;
+    }
+    table
+};
+
+impl Piece {
+    /// the slot the piece is in and its orientation there.
+    pub fn locate(self, cube: ReprCube) -> (u8, u8) {
+        match self {
+            Piece::Corner(corner) => {
+                let slot = cube
+                    .corner_perm
+                    .0
+                    .0
+                    .iter()
+                    .position(|&c| c == corner)
+                    .unwrap();
+                (slot as u8, cube.corner_orient.0[slot])
+            }
+            Piece::Edge(edge) => {
+                let slot = cube.edge_perm.0.0.iter().position(|&e| e == edge).unwrap();
+                (slot as u8, cube.edge_orient.0[slot])
+            }
+        }
+    }
+
+    /// the slot and orientation of the piece after `mv`, if it was at `slot` with `orient`.
+    pub fn apply_move(self, (slot, orient): (u8, u8), mv: CubeMove) -> (u8, u8) {
+        match self {
+            Piece::Corner(_) => {
+                let slot = SLOT_AFTER[mv.into_index()][slot as usize];
+                let twist = mv.into_corner_orient().0[slot as usize];
+                (slot, (orient + twist) % 3)
+            }
+            Piece::Edge(_) => {
+                let slot = SLOT_AFTER[mv.into_index()][8 + slot as usize];
+                let flip = mv.into_edge_orient().0[slot as usize];
+                (slot, (orient + flip) % 2)
+            }
+        }
+    }
+
+    const fn solved(self) -> (u8, u8) {
+        match self {
+            Piece::Corner(slot) | Piece::Edge(slot) => (slot, 0),
+        }
+    }
+
+    // 8 corner slots with 3 orientations and 12 edge slots with 2 both make 24 digits.
+    const fn digit(self, (slot, orient): (u8, u8)) -> usize {
+        match self {
+            Piece::Corner(_) => slot as usize * 3 + orient as usize,
+            Piece::Edge(_) => slot as usize * 2 + orient as usize,
+        }
+    }
+
+    const fn location_of_digit(self, digit: usize) -> (u8, u8) {
+        match self {
+            Piece::Corner(_) => ((digit / 3) as u8, (digit % 3) as u8),
+            Piece::Edge(_) => ((digit / 2) as u8, (digit % 2) as u8),
+        }
+    }
+}
+
+/// the index of the slots and orientations of `pieces` in their `pattern_database`, one base 24
+/// digit per piece.
+pub fn pattern_index(pieces: &[Piece], pattern: impl IntoIterator<Item = (u8, u8)>) -> usize {
+    pieces
+        .iter()
+        .zip(pattern)
+        .fold((0, 1), |(index, scale), (piece, location)| {
+            (index + piece.digit(location) * scale, scale * 24)
+        })
+        .0
+}
+
+/// the index of `pieces` in their `pattern_database` for `cube`.
+pub fn cube_pattern_index(pieces: &[Piece], cube: ReprCube) -> usize {
+    pattern_index(pieces, pieces.iter().map(|piece| piece.locate(cube)))
+}
+
+/// the exact number of moves to solve `pieces` from every pattern, ignoring the rest of the cube.
+/// patterns that can't happen, like two pieces in one slot, are `u8::MAX`.
+pub fn pattern_database(pieces: &[Piece]) -> Box<[u8]> {
+    let start = pattern_index(pieces, pieces.iter().map(|piece| piece.solved()));
+    breadth_first(24usize.pow(pieces.len() as u32), start, |index| {
+        let pattern: Vec<_> = pieces
+            .iter()
+            .scan(index, |index, piece| {
+                let location = piece.location_of_digit(*index % 24);
+                *index /= 24;
+                Some(location)
+            })
+            .collect();
+        CubeMove::all_iter().map(move |mv| {
+            pattern_index(
+                pieces,
+                pieces
+                    .iter()
+                    .zip(&pattern)
+                    .map(|(piece, &location)| piece.apply_move(location, mv)),
+            )
+        })
+    })
+}
+
+/// the distance of every state from `start`, given the states one move away from each.
+pub fn breadth_first<I: Iterator<Item = usize>>(
+    size: usize,
+    start: usize,
+    successors: impl Fn(usize) -> I,
+) -> Box<[u8]> {
+    let mut distances = vec![u8::MAX; size].into_boxed_slice();
+    let mut queue = VecDeque::from([start]);
+    distances[start] = 0;
+
+    while let Some(index) = queue.pop_front() {
+        for next in successors(index) {
+            if distances[next] == u8::MAX {
+                distances[next] = distances[index] + 1;
+                queue.push_back(next);
+            }
+        }
+    }
+
+    distances
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cube;
+
+    use super::*;
+
+    #[test]
+    fn pieces_follow_moves() {
+        let pieces = [
+            Piece::Corner(4),
+            Piece::Edge(8),
+            Piece::Corner(7),
+            Piece::Edge(1),
+        ];
+        let moves = [
+            CubeMove::R1,
+            CubeMove::U2,
+            CubeMove::F3,
+            CubeMove::L1,
+            CubeMove::D1,
+        ];
+
+        let mut cube = ReprCube::SOLVED;
+        let mut pattern = pieces.map(|piece| piece.locate(cube));
+        for mv in moves {
+            cube = cube.apply_move(mv);
+            pattern = std::array::from_fn(|i| pieces[i].apply_move(pattern[i], mv));
+            assert_eq!(pattern, pieces.map(|piece| piece.locate(cube)));
+        }
+
+        let database = pattern_database(&pieces[..2]);
+        assert_eq!(
+            database[cube_pattern_index(&pieces[..2], ReprCube::SOLVED)],
+            0
+        );
+        assert_eq!(database[cube_pattern_index(&pieces[..2], cube![R])], 1);
+        assert_eq!(database[cube_pattern_index(&pieces[..2], cube![R U Rp])], 2);
+    }
+}
@@ -0,0 +1,402 @@
+use crate::{
+    cube_ops::{
+        algorithm::Algorithm, cube_move::CubeMove, cube_sym::CubeSymmetry,
+        partial_reprs::edge_perm::EdgePerm, repr_cube::ReprCube,
+    },
+    permutation_math::permutation::Permutation,
+    steps::{
+        goal::Goal,
+        last_layer::{LlRecognizer, LlSet},
+    },
+};
+
+const SLOT_NAMES: [&str; 4] = ["FR", "FL", "BR", "BL"];
+
+/// `R L'`, which is what an `M` turn does to the cube relative to the centers.
+const M_TURN: ReprCube = ReprCube::SOLVED
+    .apply_move(CubeMove::R1)
+    .apply_move(CubeMove::L3);
+
+/// the cross edges of ZZ's EOLine, DF and DB.
+const LINE: Goal = Goal {
+    corners: 0,
+    edges: (1 << 4) | (1 << 5),
+};
+
+/// The first two blocks of Roux.
+const F2B: Goal = Goal::ROUX_FIRST_BLOCK.union(Goal::ROUX_SECOND_BLOCK);
+
+/// The first two blocks of Roux and the last layer corners, what is left after CMLL.
+const F2B_CORNERS: Goal = Goal {
+    corners: 0xff,
+    edges: F2B.edges,
+};
+
+/// A speedsolving method, by the steps a solve is split into.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Method {
+    /// cross, four F2L pairs, OLL and PLL.
+    Cfop,
+    /// first block, second block, CMLL and LSE.
+    Roux,
+    /// EOLine, the left and right blocks and the last layer.
+    Zz,
+}
+
+impl Method {
+    /// the names of the steps, in order.
+    pub fn step_names(self) -> &'static [&'static str] {
+        match self {
+            Method::Cfop => &["Cross", "F2L 1", "F2L 2", "F2L 3", "F2L 4", "OLL", "PLL"],
+            Method::Roux => &["First block", "Second block", "CMLL", "LSE"],
+            Method::Zz => &["EOLine", "Left block", "Right block", "Last layer"],
+        }
+    }
+
+    // whether `cube` has step `step` and every step before it done.
+    fn step_done(self, step: usize, cube: ReprCube) -> bool {
+        let edges_oriented = cube.edge_orient.0.iter().all(|&orient| orient == 0);
+        let corners_oriented = cube.corner_orient.0.iter().all(|&orient| orient == 0);
+        match (self, step) {
+            (Method::Cfop, 0) => Goal::CROSS.is_solved(cube),
+            (Method::Cfop, 1..=4) => Goal::CROSS.is_solved(cube) && solved_pairs(cube) >= step,
+            (Method::Cfop, 5) => Goal::F2L.is_solved(cube) && edges_oriented && corners_oriented,
+            (Method::Roux, 0) => m_alignment(cube, Goal::ROUX_FIRST_BLOCK).is_some(),
+            (Method::Roux, 1) => m_alignment(cube, F2B).is_some(),
+            (Method::Roux, 2) => m_alignment(cube, F2B_CORNERS).is_some(),
+            (Method::Zz, 0) => edges_oriented && LINE.is_solved(cube),
+            (Method::Zz, 1) => LINE.union(Goal::ROUX_FIRST_BLOCK).is_solved(cube),
+            (Method::Zz, 2) => Goal::F2L.is_solved(cube),
+            _ => cube == ReprCube::SOLVED,
+        }
+    }
+}
+
+/// One step of a reconstructed solve.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct ReconstructionStep {
+    pub name: &'static str,
+    /// the moves of the step as they were done.
+    pub moves: Algorithm,
+    /// how many of `moves` cancelled, with each other or with the steps before.
+    pub cancelled: usize,
+    /// the slot of an F2L pair, or the last layer case, e.g. "OLL 27". for CMLL it is the corner
+    /// orientation the case belongs to, e.g. "CMLL S", and for the ZZ last layer the OLL case, or
+    /// the PLL case if the last layer was oriented.
+    pub case: Option<String>,
+}
+
+/// A solve split into the steps of a method.
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Reconstruction {
+    pub method: Method,
+    /// the rotation the solve was done in: the steps are solved on `cube.conjugate(orientation)`.
+    pub orientation: CubeSymmetry,
+    /// the steps that were finished, in order.
+    pub steps: Vec<ReconstructionStep>,
+    /// the moves after the last finished step.
+    pub unfinished: Algorithm,
+}
+
+impl Reconstruction {
+    /// whether every step of the method was finished.
+    pub fn is_complete(&self) -> bool {
+        self.steps.len() == self.method.step_names().len()
+    }
+
+    /// the length of the whole solve, after cancellations.
+    pub fn move_count(&self) -> usize {
+        let moves = self
+            .steps
+            .iter()
+            .map(|step| &step.moves)
+            .chain([&self.unfinished])
+            .flat_map(|moves| &moves.moves)
+            .copied()
+            .collect();
+        Algorithm::new(moves).simplify().len()
+    }
+}
+
+/// One line per step, `R U R' // F2L 1 (3): FR`, then the move count.
+impl std::fmt::Display for Reconstruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for step in &self.steps {
+            write!(f, "{} // {} ({}", step.moves, step.name, step.moves.len())?;
+            if step.cancelled > 0 {
+                write!(f, ", {} cancelled", step.cancelled)?;
+            }
+            f.write_str(")")?;
+            if let Some(case) = &step.case {
+                write!(f, ": {case}")?;
+            }
+            writeln!(f)?;
+        }
+        if !self.unfinished.is_empty() {
+            writeln!(f, "{} // unfinished", self.unfinished)?;
+        }
+        write!(f, "{} moves", self.move_count())
+    }
+}
+
+/// Splits solves into the steps of a method, recognizing the cases along the way.
+pub struct Reconstructor {
+    recognizer: LlRecognizer,
+}
+
+impl Default for Reconstructor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Reconstructor {
+    pub fn new() -> Self {
+        Self {
+            recognizer: LlRecognizer::new(),
+        }
+    }
+
+    /// split `solution` of `scramble` into the steps of `method`.
+    ///
+    /// each step ends at the first move after which it is done. the solve can be in any
+    /// orientation; the one where the most steps get done, and the earliest, is used.
+    pub fn analyze(
+        &self,
+        scramble: ReprCube,
+        solution: &Algorithm,
+        method: Method,
+    ) -> Reconstruction {
+        // the cube after each prefix of the solution.
+        let states: Vec<ReprCube> = std::iter::once(scramble)
+            .chain(solution.moves.iter().scan(scramble, |cube, &mv| {
+                *cube = cube.apply_move(mv);
+                Some(*cube)
+            }))
+            .collect();
+
+        let (orientation, ends) = CubeSymmetry::all_iter()
+            .filter(|sym| sym.0 & 1 == 0)
+            .map(|sym| (sym, step_ends(&states, sym, method)))
+            .min_by(|(_, a), (_, b)| b.len().cmp(&a.len()).then_with(|| a.cmp(b)))
+            .unwrap();
+
+        // the length of each prefix of the solution, after cancellations.
+        let mut prefix = Algorithm::default();
+        let mut simplified_len = vec![0];
+        for &mv in &solution.moves {
+            prefix.moves.push(mv);
+            prefix = prefix.simplify();
+            simplified_len.push(prefix.len());
+        }
+
+        let mut steps = Vec::with_capacity(ends.len());
+        let mut start = 0;
+        for (step, &end) in ends.iter().enumerate() {
+            let before = states[start].conjugate(orientation);
+            let after = states[end].conjugate(orientation);
+            steps.push(ReconstructionStep {
+                name: method.step_names()[step],
+                moves: Algorithm::new(solution.moves[start..end].to_vec()),
+                cancelled: (end - start) + simplified_len[start] - simplified_len[end],
+                case: self.case(method, step, before, after),
+            });
+            start = end;
+        }
+
+        Reconstruction {
+            method,
+            orientation,
+            steps,
+            unfinished: Algorithm::new(solution.moves[start..].to_vec()),
+        }
+    }
+
+    // what step `step` solved, going from `before` to `after`.
+    fn case(
+        &self,
+        method: Method,
+        step: usize,
+        before: ReprCube,
+        after: ReprCube,
+    ) -> Option<String> {
+        let last_layer = |set| {
+            self.recognizer
+                .recognize(before, set)
+                .ok()
+                .map(|case| case.to_string())
+        };
+        match (method, step) {
+            (Method::Cfop, 1..=4) => {
+                let slots = (0..4)
+                    .filter(|&slot| {
+                        let pair = Goal::F2L_PAIRS[slot];
+                        !pair.is_solved(before) && pair.is_solved(after)
+                    })
+                    .map(|slot| SLOT_NAMES[slot])
+                    .collect::<Vec<_>>();
+                (!slots.is_empty()).then(|| slots.join("+"))
+            }
+            (Method::Cfop, 5) => last_layer(LlSet::Oll),
+            (Method::Cfop, 6) => last_layer(LlSet::Pll),
+            (Method::Roux, 2) => {
+                let j = m_alignment(before, F2B)?;
+                let corners = corners_only(before.then(M_TURN.pow(j)));
+                Some(format!(
+                    "CMLL {}",
+                    self.recognizer.corner_orientation(corners)
+                ))
+            }
+            (Method::Zz, 3) => last_layer(LlSet::Pll).or_else(|| last_layer(LlSet::Oll)),
+            _ => None,
+        }
+    }
+}
+
+// the number of F2L pairs solved.
+fn solved_pairs(cube: ReprCube) -> usize {
+    Goal::F2L_PAIRS
+        .iter()
+        .filter(|pair| pair.is_solved(cube))
+        .count()
+}
+
+// the number of `M_TURN`s after which `goal` is solved on `cube`, since M turns move the centers
+// the blocks are built around.
+fn m_alignment(cube: ReprCube, goal: Goal) -> Option<u32> {
+    (0..4).find(|&j| goal.is_solved(cube.then(M_TURN.pow(j))))
+}
+
+// the index after each step of `method` that got done on the `states` conjugated by `sym`.
+fn step_ends(states: &[ReprCube], sym: CubeSymmetry, method: Method) -> Vec<usize> {
+    let mut ends = Vec::new();
+    let mut start = 0;
+    for step in 0..method.step_names().len() {
+        let Some(end) =
+            (start..states.len()).find(|&i| method.step_done(step, states[i].conjugate(sym)))
+        else {
+            break;
+        };
+        ends.push(end);
+        start = end;
+    }
+    ends
+}
+
+// the corners of `cube` with every edge oriented and in place, which keeps their orientation. odd
+// corner permutations get UF and UR swapped to make a valid cube.
+fn corners_only(cube: ReprCube) -> ReprCube {
+    let edge_perm = if cube.corner_perm.0.is_odd() {
+        EdgePerm(Permutation::const_from_array([
+            2, 1, 0, 3, 4, 5, 6, 7, 8, 9, 10, 11,
+        ]))
+    } else {
+        EdgePerm::SOLVED
+    };
+    ReprCube {
+        edge_perm,
+        edge_orient: ReprCube::SOLVED.edge_orient,
+        ..cube
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn alg(s: &str) -> Algorithm {
+        s.parse().unwrap()
+    }
+
+    // a solve made of `steps`, and the scramble it solves.
+    fn solve(steps: &[&str]) -> (ReprCube, Algorithm) {
+        let solution = alg(&steps.join(" "));
+        (solution.cube().inverse(), solution)
+    }
+
+    fn lengths(reconstruction: &Reconstruction) -> Vec<usize> {
+        reconstruction
+            .steps
+            .iter()
+            .map(|step| step.moves.len())
+            .collect()
+    }
+
+    #[test]
+    fn cfop_steps() {
+        let reconstructor = Reconstructor::new();
+        let (scramble, solution) = solve(&[
+            "D2 F R'",
+            "R U R'",
+            "L' U' L",
+            "R' U R",
+            "L U' L' U",
+            "U' R U R' U R U2 R'",
+            "R U R' U' R' F R2 U' R' U' R U R' F'",
+        ]);
+
+        let reconstruction = reconstructor.analyze(scramble, &solution, Method::Cfop);
+        assert!(reconstruction.is_complete());
+        assert!(reconstruction.unfinished.is_empty());
+        assert_eq!(reconstruction.orientation, CubeSymmetry(0));
+        assert_eq!(lengths(&reconstruction), [3, 3, 3, 3, 3, 9, 14]);
+
+        let steps = &reconstruction.steps;
+        assert_eq!(steps[0].name, "Cross");
+        let slots: Vec<_> = steps[1..5].iter().map(|step| step.case.clone()).collect();
+        assert_eq!(slots, ["FR", "FL", "BR", "BL"].map(|s| Some(s.to_string())));
+        assert_eq!(steps[5].case.as_deref(), Some("OLL 27"));
+        assert_eq!(steps[6].case.as_deref(), Some("PLL T"));
+
+        // the AUF that the OLL undid cancels, and the end of the OLL with the start of the PLL.
+        assert_eq!(steps[5].cancelled, 2);
+        assert!(steps[6].cancelled > 0);
+        assert_eq!(reconstruction.move_count(), solution.simplify().len());
+        assert!(
+            reconstruction
+                .to_string()
+                .contains("U U' R U R' U R U2 R' // OLL (9, 2 cancelled): OLL 27")
+        );
+
+        // the same solve, held in another orientation.
+        let sym = CubeSymmetry(16);
+        let rotated = reconstructor.analyze(
+            scramble.conjugate(sym),
+            &solution.conjugate(sym),
+            Method::Cfop,
+        );
+        assert_ne!(rotated.orientation, CubeSymmetry(0));
+        assert_eq!(lengths(&rotated), lengths(&reconstruction));
+        assert_eq!(rotated.steps[6].case.as_deref(), Some("PLL T"));
+
+        // a solve that stops before the last layer.
+        let unfinished = Algorithm::new(solution.moves[..17].to_vec());
+        let reconstruction = reconstructor.analyze(scramble, &unfinished, Method::Cfop);
+        assert_eq!(reconstruction.steps.len(), 5);
+        assert_eq!(reconstruction.unfinished, alg("U U'"));
+    }
+
+    #[test]
+    fn roux_and_zz_steps() {
+        let reconstructor = Reconstructor::new();
+
+        let (scramble, solution) = solve(&["L F' D", "R2 U R'", "R U R' U R U2 R'", "M' U2 M U2"]);
+        let reconstruction = reconstructor.analyze(scramble, &solution, Method::Roux);
+        assert!(reconstruction.is_complete());
+        // each M is two face turns.
+        assert_eq!(lengths(&reconstruction), [3, 3, 7, 6]);
+        let cmll = reconstruction.steps[2].case.as_deref().unwrap();
+        assert_eq!(cmll, "CMLL S");
+
+        let (scramble, solution) = solve(&[
+            "F B' D2",
+            "L' U L",
+            "R U' R'",
+            "R U R' U' R' F R2 U' R' U' R U R' F'",
+        ]);
+        let reconstruction = reconstructor.analyze(scramble, &solution, Method::Zz);
+        assert!(reconstruction.is_complete());
+        assert_eq!(lengths(&reconstruction), [3, 3, 3, 14]);
+        assert_eq!(reconstruction.steps[3].case.as_deref(), Some("PLL T"));
+    }
+}
@@ -0,0 +1,233 @@
+use std::{str::FromStr, sync::LazyLock};
+
+use rand::distr::{Distribution, StandardUniform};
+use three_by_three::{
+    CornerOrient, CornerPerm, CubeMove, EdgeOrient, EdgePerm, Permutation, parse_moves,
+};
+
+/// The number of states of a 2x2x2 up to rotations of the whole cube, `7! * 3^6`.
+pub const STATE_COUNT: usize = 3_674_160;
+
+const ORIENT_COUNT: usize = 729;
+
+/// A 2x2x2, as the corners of a 3x3x3 in the slot order of `three_by_three::ReprCube`: UFR UFL
+/// UBR UBL DFR DFL DBR DBL.
+///
+/// With no centers, a cube turned as a whole is in the same state, and `D` does to the pieces what
+/// `U` and a rotation do. `normalize` picks the rotation that keeps DBL solved, which `U`, `R` and
+/// `F` turns never move.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct ReprCube {
+    pub corner_perm: CornerPerm,
+    pub corner_orient: CornerOrient,
+}
+
+// the 24 rotations of the whole cube, as what they do to the corners.
+static ROTATIONS: LazyLock<Vec<ReprCube>> = LazyLock::new(|| {
+    // x turns the R face like R and the L face like L', y turns U like U and D like D'.
+    let x = ReprCube::SOLVED
+        .apply_move(CubeMove::R1)
+        .apply_move(CubeMove::L3);
+    let y = ReprCube::SOLVED
+        .apply_move(CubeMove::U1)
+        .apply_move(CubeMove::D3);
+
+    let mut rotations = vec![ReprCube::SOLVED];
+    let mut i = 0;
+    while i < rotations.len() {
+        for next in [rotations[i].then(x), rotations[i].then(y)] {
+            if !rotations.contains(&next) {
+                rotations.push(next);
+            }
+        }
+        i += 1;
+    }
+    rotations
+});
+
+impl ReprCube {
+    pub const SOLVED: Self = Self {
+        corner_perm: CornerPerm::SOLVED,
+        corner_orient: CornerOrient::SOLVED,
+    };
+
+    const fn into_3x3(self) -> three_by_three::ReprCube {
+        three_by_three::ReprCube {
+            corner_perm: self.corner_perm,
+            corner_orient: self.corner_orient,
+            edge_perm: EdgePerm::SOLVED,
+            edge_orient: EdgeOrient::SOLVED,
+        }
+    }
+
+    const fn from_3x3(cube: three_by_three::ReprCube) -> Self {
+        Self {
+            corner_perm: cube.corner_perm,
+            corner_orient: cube.corner_orient,
+        }
+    }
+
+    pub const fn apply_move(self, mv: CubeMove) -> Self {
+        Self::from_3x3(self.into_3x3().apply_move(mv))
+    }
+
+    pub const fn then(self, other: Self) -> Self {
+        Self::from_3x3(self.into_3x3().then(other.into_3x3()))
+    }
+
+    pub const fn inverse(self) -> Self {
+        Self::from_3x3(self.into_3x3().inverse())
+    }
+
+    /// the 24 rotations of the whole cube, the identity first. each is a solved cube.
+    pub fn rotations() -> &'static [ReprCube] {
+        &ROTATIONS
+    }
+
+    /// the same cube turned as a whole so that the DBL corner is in place and oriented.
+    pub fn normalize(self) -> Self {
+        self.then(self.normalizing_rotation())
+    }
+
+    // the rotation that `normalize` turns the cube by.
+    pub(crate) fn normalizing_rotation(self) -> Self {
+        *ROTATIONS
+            .iter()
+            .find(|&&rotation| {
+                let turned = self.then(rotation);
+                turned.corner_perm.0.0[7] == 7 && turned.corner_orient.0[7] == 0
+            })
+            .unwrap()
+    }
+
+    /// whether every face is one color, whichever way the cube is held.
+    pub fn is_solved(self) -> bool {
+        self.normalize() == Self::SOLVED
+    }
+
+    /// the index of the normalized cube, below `STATE_COUNT`. the permutation of the other seven
+    /// corners, then the orientation of the first six.
+    pub fn index(self) -> usize {
+        let cube = self.normalize();
+        let perm = cube.corner_perm.0.0;
+        let mut perm_index = 0;
+        for i in 0..7 {
+            let smaller = perm[i + 1..7].iter().filter(|&&p| p < perm[i]).count();
+            perm_index = perm_index * (7 - i) + smaller;
+        }
+        let orient_index = cube.corner_orient.0[..6]
+            .iter()
+            .rev()
+            .fold(0, |index, &orient| index * 3 + orient as usize);
+        perm_index * ORIENT_COUNT + orient_index
+    }
+
+    /// the normalized cube with index `index`.
+    pub fn from_index(index: usize) -> Self {
+        let (mut perm_index, mut orient_index) = (index / ORIENT_COUNT, index % ORIENT_COUNT);
+
+        // the Lehmer code, from the last digit.
+        let mut smaller = [0; 7];
+        for (i, digit) in smaller.iter_mut().enumerate().rev() {
+            *digit = perm_index % (7 - i);
+            perm_index /= 7 - i;
+        }
+        let mut remaining: Vec<u8> = (0..7).collect();
+        let mut perm = [7; 8];
+        for (slot, &digit) in smaller.iter().enumerate() {
+            perm[slot] = remaining.remove(digit);
+        }
+
+        let mut orient = [0; 8];
+        for slot in orient.iter_mut().take(6) {
+            *slot = (orient_index % 3) as u8;
+            orient_index /= 3;
+        }
+        orient[6] = (3 - orient.iter().sum::<u8>() % 3) % 3;
+
+        Self {
+            corner_perm: CornerPerm(Permutation(perm)),
+            corner_orient: CornerOrient(orient),
+        }
+    }
+}
+
+/// the corners of a 3x3x3.
+impl From<three_by_three::ReprCube> for ReprCube {
+    fn from(cube: three_by_three::ReprCube) -> Self {
+        Self::from_3x3(cube)
+    }
+}
+
+/// the cube that a sequence of moves in the notation of `three_by_three::parse_moves` turns a
+/// solved cube into.
+impl FromStr for ReprCube {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        Ok(parse_moves(s)?
+            .into_iter()
+            .fold(Self::SOLVED, |cube, mv| cube.apply_move(mv)))
+    }
+}
+
+/// a uniformly random normalized cube.
+impl Distribution<ReprCube> for StandardUniform {
+    fn sample<R: rand::Rng + ?Sized>(&self, rng: &mut R) -> ReprCube {
+        ReprCube::from_index(rng.random_range(0..STATE_COUNT))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    fn cube(s: &str) -> ReprCube {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn rotations_and_normalize() {
+        let rotations = ReprCube::rotations();
+        assert_eq!(rotations.len(), 24);
+        assert_eq!(rotations[0], ReprCube::SOLVED);
+        for &rotation in rotations {
+            assert!(rotation.is_solved());
+            assert_eq!(rotation.normalize(), ReprCube::SOLVED);
+        }
+
+        // a D turn is a U turn of the cube held another way.
+        assert_eq!(cube("D").normalize(), cube("U").normalize());
+        assert_eq!(cube("R L'").normalize(), ReprCube::SOLVED);
+        assert_eq!(cube("R U").normalize(), cube("R U"));
+        assert!(!cube("R").is_solved());
+
+        let three = "R U' F2".parse::<three_by_three::Algorithm>().unwrap();
+        assert_eq!(ReprCube::from(three.cube()), cube("R U' F2"));
+    }
+
+    #[test]
+    fn index_round_trip() {
+        assert_eq!(ReprCube::SOLVED.index(), 0);
+        assert_eq!(ReprCube::from_index(0), ReprCube::SOLVED);
+        assert_eq!(
+            ReprCube::from_index(STATE_COUNT - 1).index(),
+            STATE_COUNT - 1
+        );
+
+        let mut rng = ChaCha8Rng::seed_from_u64(3);
+        for _ in 0..1000 {
+            let index = rng.random_range(0..STATE_COUNT);
+            let cube = ReprCube::from_index(index);
+            assert_eq!(cube.normalize(), cube);
+            assert_eq!(cube.index(), index);
+
+            // every rotation of the cube is the same state.
+            let rotation = ReprCube::rotations()[rng.random_range(0..24)];
+            assert_eq!(cube.then(rotation).index(), index);
+        }
+    }
+}
@@ -0,0 +1,169 @@
+use three_by_three::{Algorithm, CubeMove};
+
+use crate::cube::{ReprCube, STATE_COUNT};
+
+/// The turns that keep DBL in place. every normalized cube is solved by them alone, in as few
+/// moves as with all 18 turns.
+const MOVES: [CubeMove; 9] = [
+    CubeMove::U1,
+    CubeMove::U2,
+    CubeMove::U3,
+    CubeMove::R1,
+    CubeMove::R2,
+    CubeMove::R3,
+    CubeMove::F1,
+    CubeMove::F2,
+    CubeMove::F3,
+];
+
+const PERM_COUNT: usize = 5040;
+const ORIENT_COUNT: usize = 729;
+
+const UNVISITED: u8 = u8::MAX;
+
+/// The exact number of half turn metric moves that solve each of the `STATE_COUNT` cubes.
+pub struct DistanceTable {
+    distances: Vec<u8>,
+    // the index parts a move leads to, for each permutation and each orientation.
+    perm_moves: Vec<[u16; 9]>,
+    orient_moves: Vec<[u16; 9]>,
+}
+
+impl Default for DistanceTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DistanceTable {
+    /// a breadth first search over every state from the solved cube.
+    pub fn new() -> Self {
+        let move_table = |count: usize, scale: usize, part: fn(usize) -> usize| {
+            (0..count)
+                .map(|i| {
+                    let cube = ReprCube::from_index(i * scale);
+                    MOVES.map(|mv| part(cube.apply_move(mv).index()) as u16)
+                })
+                .collect::<Vec<_>>()
+        };
+        let perm_moves = move_table(PERM_COUNT, ORIENT_COUNT, |index| index / ORIENT_COUNT);
+        let orient_moves = move_table(ORIENT_COUNT, 1, |index| index % ORIENT_COUNT);
+
+        let mut table = Self {
+            distances: vec![UNVISITED; STATE_COUNT],
+            perm_moves,
+            orient_moves,
+        };
+        table.distances[0] = 0;
+        let mut depth = 0;
+        let mut visited = 1;
+        while visited < STATE_COUNT {
+            for index in 0..STATE_COUNT {
+                if table.distances[index] != depth {
+                    continue;
+                }
+                for next in table.neighbours(index) {
+                    if table.distances[next] == UNVISITED {
+                        table.distances[next] = depth + 1;
+                        visited += 1;
+                    }
+                }
+            }
+            depth += 1;
+        }
+        table
+    }
+
+    // the index after each of `MOVES` from `index`.
+    fn neighbours(&self, index: usize) -> [usize; 9] {
+        let perm = &self.perm_moves[index / ORIENT_COUNT];
+        let orient = &self.orient_moves[index % ORIENT_COUNT];
+        std::array::from_fn(|i| perm[i] as usize * ORIENT_COUNT + orient[i] as usize)
+    }
+
+    pub fn distance(&self, cube: ReprCube) -> u8 {
+        self.distances[cube.index()]
+    }
+
+    /// how many states are at each distance, from solved to the farthest.
+    pub fn histogram(&self) -> Vec<usize> {
+        let mut histogram = Vec::new();
+        for &distance in &self.distances {
+            let distance = distance as usize;
+            if histogram.len() <= distance {
+                histogram.resize(distance + 1, 0);
+            }
+            histogram[distance] += 1;
+        }
+        histogram
+    }
+
+    /// an optimal solution, after which every face of `cube` is one color.
+    pub fn solve(&self, cube: ReprCube) -> Algorithm {
+        // the solution of the normalized cube, turned back to how `cube` is held.
+        let rotation = cube.normalizing_rotation();
+        let unrotate = |mv: CubeMove| {
+            let turn = rotation
+                .then(ReprCube::SOLVED.apply_move(mv))
+                .then(rotation.inverse());
+            CubeMove::all_iter()
+                .find(|&face_turn| ReprCube::SOLVED.apply_move(face_turn) == turn)
+                .unwrap()
+        };
+
+        let mut index = cube.index();
+        let mut moves = Vec::with_capacity(self.distances[index] as usize);
+        while self.distances[index] > 0 {
+            let (i, next) = self
+                .neighbours(index)
+                .into_iter()
+                .enumerate()
+                .find(|&(_, next)| self.distances[next] < self.distances[index])
+                .unwrap();
+            moves.push(unrotate(MOVES[i]));
+            index = next;
+        }
+        Algorithm::new(moves)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    #[test]
+    fn distances_and_solutions() {
+        let table = DistanceTable::new();
+
+        // the known counts for the half turn metric, up to the 11 moves of the hardest states.
+        assert_eq!(
+            table.histogram(),
+            [
+                1, 9, 54, 321, 1847, 9992, 50136, 227536, 870072, 1887748, 623800, 2644
+            ]
+        );
+        assert_eq!(table.distance(ReprCube::SOLVED), 0);
+        assert_eq!(table.distance("R U R' U'".parse().unwrap()), 4);
+        assert_eq!(table.distance("D L B".parse().unwrap()), 3);
+
+        let mut rng = ChaCha8Rng::seed_from_u64(5);
+        for _ in 0..200 {
+            let cube: ReprCube =
+                rand::distr::Distribution::sample(&rand::distr::StandardUniform, &mut rng);
+            let rotation = ReprCube::rotations()[rand::Rng::random_range(&mut rng, 0..24)];
+            // held in any orientation.
+            let cube = cube.then(rotation);
+
+            let solution = table.solve(cube);
+            assert_eq!(solution.len(), table.distance(cube) as usize);
+            let solved = solution
+                .moves
+                .iter()
+                .fold(cube, |cube, &mv| cube.apply_move(mv));
+            assert!(solved.is_solved());
+        }
+    }
+}
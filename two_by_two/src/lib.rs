@@ -0,0 +1,9 @@
+mod cube;
+mod distance_table;
+mod scramble;
+
+pub use cube::{ReprCube, STATE_COUNT};
+pub use distance_table::DistanceTable;
+pub use scramble::{MIN_SCRAMBLE_DISTANCE, random_scramble};
+
+pub use three_by_three::{Algorithm, CubeMove, parse_moves};
@@ -0,0 +1,41 @@
+use rand::{
+    Rng,
+    distr::{Distribution, StandardUniform},
+};
+use three_by_three::Algorithm;
+
+use crate::{cube::ReprCube, distance_table::DistanceTable};
+
+/// Scrambles closer to solved than this are rejected, as WCA scrambles for the 2x2x2 are.
+pub const MIN_SCRAMBLE_DISTANCE: u8 = 4;
+
+/// a scramble to a uniformly random state at least `MIN_SCRAMBLE_DISTANCE` moves from solved, as
+/// short as it can be.
+pub fn random_scramble(table: &DistanceTable, rng: &mut impl Rng) -> Algorithm {
+    loop {
+        let cube: ReprCube = StandardUniform.sample(rng);
+        if table.distance(cube) >= MIN_SCRAMBLE_DISTANCE {
+            return table.solve(cube).inverse();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+
+    #[test]
+    fn scrambles() {
+        let table = DistanceTable::new();
+        let mut rng = ChaCha8Rng::seed_from_u64(9);
+        for _ in 0..50 {
+            let scramble = random_scramble(&table, &mut rng);
+            let cube: ReprCube = scramble.to_string().parse().unwrap();
+            assert_eq!(table.distance(cube) as usize, scramble.len());
+            assert!(scramble.len() >= MIN_SCRAMBLE_DISTANCE as usize);
+        }
+    }
+}